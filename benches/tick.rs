@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use sp_gol::{game::Board, rule::Rule, topology::Topology};
+
+// Compare against `cargo bench --features parallel` to see the multithreaded speedup.
+fn tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    group.sample_size(10);
+    for size in [256, 1024, 2048] {
+        let mut board = Board::new(size, size, None, 0.0, Rule::default(), Topology::default());
+        board.randomize();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &board, |b, board| {
+            b.iter_batched_ref(
+                || board.clone(),
+                |board| board.tick(),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tick);
+criterion_main!(benches);
@@ -0,0 +1,156 @@
+use crate::{
+    game::{Board, Cell},
+    rule::Rule,
+    topology::Topology,
+};
+
+// Torus stored 64 cells to a word, each row padded to a whole number of words.
+// Neighbor counts for 64 cells at a time are summed with bitwise adders into four
+// bit planes, so a generation never looks at an individual cell.
+pub struct BitBoard {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitBoard {
+    pub fn supports(rule: &Rule, topology: &Topology) -> bool {
+        rule.states == 2 && *topology == Topology::default()
+    }
+
+    pub fn from_board(board: &Board) -> Self {
+        let (width, height) = (board.width as usize, board.height as usize);
+        let words_per_row = width.div_ceil(64);
+        let mut bits = vec![0; words_per_row * height];
+        for (row, cells) in board.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if *cell == Cell::Alive {
+                    bits[row * words_per_row + column / 64] |= 1 << (column % 64);
+                }
+            }
+        }
+        BitBoard {
+            width,
+            height,
+            words_per_row,
+            bits,
+        }
+    }
+
+    pub fn write_to(&self, board: &mut Board) {
+        for (row, cells) in board.cells.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                *cell = match self.bits[row * self.words_per_row + column / 64] >> (column % 64) & 1
+                {
+                    1 => Cell::Alive,
+                    _ => Cell::Dead,
+                };
+            }
+        }
+    }
+
+    fn last_word_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => u64::MAX,
+            used => (1 << used) - 1,
+        }
+    }
+
+    // rows shifted so each bit holds its western (or eastern) neighbor, wrapping around
+    fn shifted(&self, row: &[u64], west: bool) -> Vec<u64> {
+        let last_bit = self.width - 1;
+        let mut out: Vec<u64> = (0..row.len())
+            .map(|i| match west {
+                true => row[i] << 1 | if i > 0 { row[i - 1] >> 63 } else { 0 },
+                false => row[i] >> 1 | row.get(i + 1).map_or(0, |next| next << 63),
+            })
+            .collect();
+        if west {
+            out[0] |= row[last_bit / 64] >> (last_bit % 64) & 1;
+        } else {
+            out[last_bit / 64] |= (row[0] & 1) << (last_bit % 64);
+        }
+        if let Some(last) = out.last_mut() {
+            *last &= self.last_word_mask();
+        }
+        out
+    }
+
+    pub fn step(&mut self, rule: &Rule) {
+        let words = self.words_per_row;
+        let rows: Vec<&[u64]> = self.bits.chunks(words).collect();
+        let west: Vec<Vec<u64>> = rows.iter().map(|row| self.shifted(row, true)).collect();
+        let east: Vec<Vec<u64>> = rows.iter().map(|row| self.shifted(row, false)).collect();
+        let counts = |set: &[bool; 9]| -> Vec<usize> { (0..9).filter(|&n| set[n]).collect() };
+        let (birth, survival) = (counts(&rule.birth), counts(&rule.survival));
+
+        let mut next = vec![0; self.bits.len()];
+        for row in 0..self.height {
+            let up = (row + self.height - 1) % self.height;
+            let down = (row + 1) % self.height;
+            for i in 0..words {
+                let neighbors = [
+                    west[up][i],
+                    rows[up][i],
+                    east[up][i],
+                    west[row][i],
+                    east[row][i],
+                    west[down][i],
+                    rows[down][i],
+                    east[down][i],
+                ];
+                // four bit planes hold the binary neighbor count of each cell
+                let mut planes = [0u64; 4];
+                for neighbor in neighbors {
+                    let mut carry = neighbor;
+                    for plane in planes.iter_mut() {
+                        let overflow = *plane & carry;
+                        *plane ^= carry;
+                        carry = overflow;
+                    }
+                }
+                let count_is = |n: usize| -> u64 {
+                    (0..4).fold(u64::MAX, |mask, bit| match n >> bit & 1 {
+                        1 => mask & planes[bit],
+                        _ => mask & !planes[bit],
+                    })
+                };
+                let alive = rows[row][i];
+                let born = birth.iter().fold(0, |mask, &n| mask | count_is(n));
+                let survives = survival.iter().fold(0, |mask, &n| mask | count_is(n));
+                next[row * words + i] = (alive & survives) | (!alive & born);
+            }
+            next[row * words + words - 1] &= self.last_word_mask();
+        }
+        self.bits = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matches_dense_tick() {
+        let rules: [Rule; 3] = [
+            Rule::default(),
+            "B36/S23".parse().unwrap(),
+            "B3678/S34678".parse().unwrap(),
+        ];
+        for (width, height) in [(64, 32), (100, 37), (130, 3)] {
+            for rule in rules {
+                let mut board = Board::new(width, height, None, 0.0, rule, Topology::default());
+                board.randomize();
+                let mut packed = BitBoard::from_board(&board);
+                for _ in 0..20 {
+                    board.tick();
+                    packed.step(&rule);
+                }
+                let mut unpacked = board.clone();
+                packed.write_to(&mut unpacked);
+                assert_eq!(unpacked.cells, board.cells, "{}x{} {}", width, height, rule);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    game::{Board, Cell},
+    rule::Rule,
+    sparse,
+};
+use std::{collections::HashMap, fmt, sync::OnceLock};
+
+// Common Life objects as (name, period, one phase with 'O' for a living cell)
+const CATALOGUE: [(&str, u64, &[&str]); 21] = [
+    ("block", 1, &["OO", "OO"]),
+    ("beehive", 1, &[".OO.", "O..O", ".OO."]),
+    ("loaf", 1, &[".OO.", "O..O", ".O.O", "..O."]),
+    ("boat", 1, &["OO.", "O.O", ".O."]),
+    ("ship", 1, &["OO.", "O.O", ".OO"]),
+    ("tub", 1, &[".O.", "O.O", ".O."]),
+    ("pond", 1, &[".OO.", "O..O", "O..O", ".OO."]),
+    ("long boat", 1, &["OO..", "O.O.", ".O.O", "..O."]),
+    ("barge", 1, &[".O..", "O.O.", ".O.O", "..O."]),
+    ("mango", 1, &[".OO..", "O..O.", ".O..O", "..OO."]),
+    ("eater", 1, &["OO..", "O.O.", "..O.", "..OO"]),
+    ("snake", 1, &["OO.O", "O.OO"]),
+    ("aircraft carrier", 1, &["OO..", "O..O", "..OO"]),
+    ("blinker", 2, &["OOO"]),
+    ("toad", 2, &[".OOO", "OOO."]),
+    ("beacon", 2, &["OO..", "OO..", "..OO", "..OO"]),
+    ("clock", 2, &["..O.", "O.O.", ".O.O", ".O.."]),
+    ("glider", 4, &[".O.", "..O", "OOO"]),
+    ("lwss", 4, &[".O..O", "O....", "O...O", "OOOO."]),
+    (
+        "mwss",
+        4,
+        &["...O..", ".O...O", "O.....", "O....O", "OOOOO."],
+    ),
+    (
+        "hwss",
+        4,
+        &["...OO..", ".O....O", "O......", "O.....O", "OOOOOO."],
+    ),
+];
+
+// The same cells whichever way round they are and wherever they are: the smallest
+// of the eight rotations and reflections once shifted to start at zero and sorted
+fn canonical(cells: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    // rows of the matrix taking (row, column) to each orientation
+    let symmetries = [
+        [1, 0, 0, 1],
+        [1, 0, 0, -1],
+        [-1, 0, 0, 1],
+        [-1, 0, 0, -1],
+        [0, 1, 1, 0],
+        [0, 1, -1, 0],
+        [0, -1, 1, 0],
+        [0, -1, -1, 0],
+    ];
+    symmetries
+        .iter()
+        .map(|[a, b, c, d]| {
+            let moved: Vec<_> = cells
+                .iter()
+                .map(|(r, col)| (a * r + b * col, c * r + d * col))
+                .collect();
+            let top = moved.iter().map(|cell| cell.0).min().unwrap_or(0);
+            let left = moved.iter().map(|cell| cell.1).min().unwrap_or(0);
+            let mut form: Vec<_> = moved
+                .into_iter()
+                .map(|(row, column)| (row - top, column - left))
+                .collect();
+            form.sort_unstable();
+            form
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// canonical forms of an object over one period, plus the one it returns to
+fn phases((_, period, rows): &(&str, u64, &[&str])) -> Vec<Vec<(i64, i64)>> {
+    let mut universe = sparse::Universe::new(Rule::default());
+    for (row, line) in rows.iter().enumerate() {
+        for (column, _) in line.chars().enumerate().filter(|(_, c)| *c == 'O') {
+            universe.set_cell(column as i64, row as i64, Cell::Alive);
+        }
+    }
+    let mut phases = Vec::new();
+    for _ in 0..=*period {
+        let (left, top, right, bottom) = universe.bounds().unwrap_or_default();
+        let width = (right - left + 1) as usize;
+        let height = (bottom - top + 1) as usize;
+        let cells: Vec<_> = universe
+            .window(left, top, width, height)
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell == Cell::Alive)
+                    .map(move |(column, _)| (row as i64, column as i64))
+            })
+            .collect();
+        phases.push(canonical(&cells));
+        universe.advance(1);
+    }
+    phases
+}
+
+// every phase of the catalogued objects, worked out the first time it's needed
+fn catalogue() -> &'static HashMap<Vec<(i64, i64)>, &'static str> {
+    static PHASES: OnceLock<HashMap<Vec<(i64, i64)>, &'static str>> = OnceLock::new();
+    PHASES.get_or_init(|| {
+        CATALOGUE
+            .iter()
+            .flat_map(|object| {
+                let mut phases = phases(object);
+                phases.pop();
+                phases.into_iter().map(|phase| (phase, object.0))
+            })
+            .collect()
+    })
+}
+
+// How many of each object the board holds, most common first. Objects are only
+// named under B3/S23; anything else is counted by its number of cells.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Census {
+    pub objects: Vec<(String, usize)>,
+}
+
+impl Census {
+    pub fn of(board: &Board) -> Self {
+        let catalogue = (board.rule == Rule::default()).then(catalogue);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for component in board.components() {
+            let name = match catalogue.and_then(|catalogue| catalogue.get(&canonical(&component))) {
+                Some(name) => name.to_string(),
+                None => format!("unknown {}-cell", component.len()),
+            };
+            *counts.entry(name).or_default() += 1;
+        }
+        let mut objects: Vec<_> = counts.into_iter().collect();
+        objects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Census { objects }
+    }
+}
+
+impl fmt::Display for Census {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, count) in &self.objects {
+            writeln!(f, "{:>6} {}", count, name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{game::Shape, topology::Topology};
+
+    #[test]
+    fn test_catalogue_phases() {
+        let mut seen = HashMap::new();
+        for object in &CATALOGUE {
+            let mut phases = phases(object);
+            assert_eq!(phases.first(), phases.last(), "{}", object.0);
+            phases.pop();
+            for phase in phases {
+                // each phase holds together as a single object
+                let height = phase.iter().map(|cell| cell.0).max().unwrap() as u16 + 1;
+                let width = phase.iter().map(|cell| cell.1).max().unwrap() as u16 + 1;
+                let mut board =
+                    Board::new(width, height, None, 0.0, Rule::default(), Topology::Plane);
+                for &(row, column) in &phase {
+                    board.flip_cell((row as usize, column as usize).into());
+                }
+                assert_eq!(board.components().len(), 1, "{}", object.0);
+                let name = seen.entry(phase).or_insert(object.0);
+                assert_eq!(*name, object.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_census() {
+        let mut board = Board::new(24, 24, None, 0.0, Rule::default(), Topology::default());
+        let glider = Shape::new(Shape::GLIDER.to_vec(), None);
+        board.add_shape((4, 4).into(), glider);
+        // blocks across the wrapped edge and in the middle, and a blinker
+        for (row, column) in [(0, 0), (0, 23), (23, 0), (23, 23), (10, 10), (10, 11)] {
+            board.flip_cell((row, column).into());
+        }
+        for (row, column) in [(11, 10), (11, 11), (18, 5), (18, 6), (18, 7), (5, 18)] {
+            board.flip_cell((row, column).into());
+        }
+        let census = Census::of(&board);
+        assert_eq!(
+            census.objects,
+            vec![
+                (String::from("block"), 2),
+                (String::from("blinker"), 1),
+                (String::from("glider"), 1),
+                (String::from("unknown 1-cell"), 1),
+            ]
+        );
+    }
+}
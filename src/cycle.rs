@@ -0,0 +1,192 @@
+use crate::game::Cell;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+// The pattern repeats every period generations from generation on, shifted by
+// displacement (x, y) each period
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cycle {
+    pub generation: u64,
+    pub period: u64,
+    pub displacement: (i64, i64),
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stabilized at gen {}, period {}",
+            self.generation, self.period
+        )?;
+        match self.displacement {
+            (0, 0) => Ok(()),
+            (x, y) => write!(f, ", speed ({},{})/{}", x, y, self.period),
+        }
+    }
+}
+
+// The size of a pattern and two independent hashes of its cells, so that two
+// different patterns are only mistaken for each other if both hashes collide
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    size: (usize, usize),
+    hashes: (u64, u64),
+}
+
+// fingerprint of the cells within bounds (left, top, right, bottom), which is the
+// same wherever the pattern is on the board
+pub fn fingerprint(
+    cells: &[Vec<Cell>],
+    bounds: Option<(usize, usize, usize, usize)>,
+) -> Fingerprint {
+    let (mut sip, mut fnv) = (DefaultHasher::new(), FnvHasher::default());
+    let mut size = (0, 0);
+    if let Some((left, top, right, bottom)) = bounds {
+        size = (right - left + 1, bottom - top + 1);
+        cells[top..=bottom].iter().for_each(|row| {
+            row[left..=right].hash(&mut sip);
+            row[left..=right].hash(&mut fnv);
+        });
+    }
+    Fingerprint {
+        size,
+        hashes: (sip.finish(), fnv.finish()),
+    }
+}
+
+// 64 bit FNV-1a, a hash unrelated to the SipHash of DefaultHasher
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// Finds when the pattern starts repeating by remembering the fingerprint of every
+// generation seen. Generations can be skipped, in which case the period is narrowed
+// down to the greatest common divisor of the gaps between repeats.
+#[derive(Default)]
+pub struct CycleDetector {
+    // generation and top left corner of the pattern for each fingerprint
+    seen: HashMap<Fingerprint, (u64, (i64, i64))>,
+    latest: Option<u64>,
+    // the cycle and the generation it was noticed at
+    cycle: Option<(Cycle, u64)>,
+}
+
+impl CycleDetector {
+    pub const MAX_STATES: usize = 1 << 16;
+
+    // the cycle, if it had been noticed by generation
+    pub fn cycle(&self, generation: u64) -> Option<Cycle> {
+        self.cycle
+            .filter(|(_, noticed)| *noticed <= generation)
+            .map(|(cycle, _)| cycle)
+    }
+
+    // record the state at generation, forgetting that generation and any later ones
+    pub fn record(&mut self, generation: u64, fingerprint: Fingerprint, corner: (i64, i64)) {
+        // recording a generation again means the pattern was edited, and no longer
+        // follows the cycle found in it
+        if self.latest.is_some_and(|latest| latest >= generation) {
+            self.seen.retain(|_, (seen, _)| *seen < generation);
+            self.cycle = None;
+        }
+        self.latest = Some(generation);
+
+        if let Some(&(seen, (x, y))) = self.seen.get(&fingerprint) {
+            let gap = generation - seen;
+            let (period, start) = match self.cycle {
+                Some((cycle, _)) => (gcd(cycle.period, gap), cycle.generation.min(seen)),
+                None => (gap, seen),
+            };
+            let scale = |moved: i64| moved * period as i64 / gap as i64;
+            let cycle = Cycle {
+                generation: start,
+                period,
+                displacement: (scale(corner.0 - x), scale(corner.1 - y)),
+            };
+            let noticed = self.cycle.map_or(generation, |(_, noticed)| noticed);
+            self.cycle = Some((cycle, noticed));
+        }
+        if self.seen.len() >= CycleDetector::MAX_STATES {
+            self.seen.clear();
+        }
+        self.seen.insert(fingerprint, (generation, corner));
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_glider_cycle() {
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        let place = |offset: usize| {
+            let mut cells = vec![vec![Cell::Dead; 8]; 8];
+            glider
+                .iter()
+                .for_each(|&(r, c)| cells[r + offset][c + offset] = Cell::Alive);
+            fingerprint(&cells, Some((offset, offset, offset + 2, offset + 2)))
+        };
+        assert_eq!(place(1), place(4));
+        assert_ne!(place(1), fingerprint(&[], None));
+        let state = |hash: u64| Fingerprint {
+            size: (1, 1),
+            hashes: (hash, hash),
+        };
+        // only one hash colliding isn't a match
+        let mut detector = CycleDetector::default();
+        detector.record(0, state(1), (0, 0));
+        let collision = Fingerprint {
+            hashes: (1, 2),
+            ..state(1)
+        };
+        detector.record(1, collision, (0, 0));
+        assert_eq!(detector.cycle(1), None);
+
+        let mut detector = CycleDetector::default();
+        for (generation, shape) in (0..12).zip([1, 2, 3, 4].iter().cycle()) {
+            let step = generation as i64 / 4;
+            detector.record(generation, state(*shape), (step, step));
+            assert_eq!(detector.cycle(generation).is_some(), generation >= 4);
+        }
+        let cycle = detector.cycle(12).unwrap();
+        assert_eq!((cycle.generation, cycle.period), (0, 4));
+        assert_eq!(cycle.displacement, (1, 1));
+
+        // skipping ahead still finds the period, and going back forgets it
+        let mut detector = CycleDetector::default();
+        for generation in [0, 12, 20] {
+            detector.record(generation, state(7), (0, 0));
+        }
+        assert_eq!(detector.cycle(20).map(|cycle| cycle.period), Some(4));
+        assert_eq!(detector.cycle(11), None);
+        detector.record(12, state(8), (0, 0));
+        assert_eq!(detector.cycle(20), None);
+    }
+}
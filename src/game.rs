@@ -1,9 +1,33 @@
+use crate::{
+    bitboard::BitBoard,
+    census::Census,
+    cycle::{self, Cycle, CycleDetector},
+    hashlife,
+    history::History,
+    pattern::{self, Pattern},
+    rule::Rule,
+    sparse,
+    stats::{ChartWindow, PopulationHistory, RateMeter, Stats},
+    timeline::Timeline,
+    topology::{GridSpec, Topology},
+    ui::GolUi,
+    viewport::Viewport,
+};
 use rand::random;
+use std::{
+    borrow::Cow,
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 use tui::{self, buffer, layout::Rect};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Cell {
     Alive,
+    // Generations rules: number of generations the cell has been decaying
+    Dying(u8),
     Dead,
 }
 
@@ -11,7 +35,16 @@ impl Cell {
     fn flip(&mut self) {
         *self = match self {
             Cell::Alive => Cell::Dead,
-            Cell::Dead => Cell::Alive,
+            Cell::Dying(_) | Cell::Dead => Cell::Alive,
+        }
+    }
+
+    pub fn decay(age: u8, states: u8) -> Cell {
+        // alive is the first state and dead the last, leaving states - 2 dying states
+        if age + 2 < states {
+            Cell::Dying(age + 1)
+        } else {
+            Cell::Dead
         }
     }
 
@@ -33,6 +66,7 @@ impl From<Cell> for buffer::Cell {
             symbol: String::from("\u{25A0}"),
             fg: match cell {
                 Cell::Alive => tui::style::Color::Black,
+                Cell::Dying(_) => tui::style::Color::Red,
                 Cell::Dead => tui::style::Color::White,
             },
             ..Default::default()
@@ -40,12 +74,18 @@ impl From<Cell> for buffer::Cell {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Position {
     pub row: usize,
     pub column: usize,
 }
 
+impl From<Position> for (usize, usize) {
+    fn from(pos: Position) -> Self {
+        (pos.row, pos.column)
+    }
+}
+
 impl From<(usize, usize)> for Position {
     fn from(t: (usize, usize)) -> Self {
         Position {
@@ -55,6 +95,18 @@ impl From<(usize, usize)> for Position {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    // a quarter turn clockwise
+    Rotate,
+    // mirror left to right
+    FlipHorizontal,
+    // mirror top to bottom
+    FlipVertical,
+    // mirror across the diagonal from the top left
+    Transpose,
+}
+
 #[derive(Clone)]
 pub struct Shape {
     pub pattern: Vec<Position>,
@@ -76,15 +128,62 @@ impl Shape {
         Shape { pattern, offset }
     }
 
-    pub fn get_cells(self, width: u16, height: u16) -> Vec<Position> {
-        let mut cells = self.pattern.clone();
-        if let Some(point) = self.offset {
-            cells.iter_mut().for_each(|pos| {
-                pos.row = (pos.row + point.row) % height as usize;
-                pos.column = (pos.column + point.column) % width as usize;
-            });
+    // shift signed (row, column) coordinates so the topmost and leftmost cells sit at zero
+    pub fn normalized(cells: Vec<(i64, i64)>) -> Self {
+        let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+        let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+        // abs_diff, as the distance between the farthest cells can exceed an i64
+        let cells = cells
+            .into_iter()
+            .map(|(row, column)| (row.abs_diff(top) as usize, column.abs_diff(left) as usize))
+            .collect();
+        Shape::new(cells, None)
+    }
+
+    // rows and columns from the top left corner to the farthest cell
+    pub fn size(&self) -> (usize, usize) {
+        let rows = self
+            .pattern
+            .iter()
+            .map(|pos| pos.row + 1)
+            .max()
+            .unwrap_or(0);
+        let columns = self
+            .pattern
+            .iter()
+            .map(|pos| pos.column + 1)
+            .max()
+            .unwrap_or(0);
+        (rows, columns)
+    }
+
+    // turn or mirror the cells in place, keeping them within the same corner
+    pub fn transform(&mut self, transform: Transform) {
+        let (rows, columns) = self.size();
+        for pos in &mut self.pattern {
+            let (row, column) = (pos.row, pos.column);
+            *pos = match transform {
+                Transform::Rotate => (column, rows - 1 - row),
+                Transform::FlipHorizontal => (row, columns - 1 - column),
+                Transform::FlipVertical => (rows - 1 - row, column),
+                Transform::Transpose => (column, row),
+            }
+            .into();
         }
-        cells
+    }
+
+    pub fn get_cells(self, topology: &Topology, width: u16, height: u16) -> Vec<Position> {
+        let (row_offset, column_offset) = self
+            .offset
+            .map_or((0, 0), |point| (point.row, point.column));
+        self.pattern
+            .into_iter()
+            .filter_map(|pos| {
+                let row = (pos.row + row_offset) as i64;
+                let column = (pos.column + column_offset) as i64;
+                topology.locate(row, column, width, height)
+            })
+            .collect()
     }
 }
 
@@ -93,30 +192,36 @@ pub struct Board {
     pub width: u16,
     pub height: u16,
     pub cells: Vec<Vec<Cell>>,
+    pub rule: Rule,
+    pub topology: Topology,
 }
 
 impl Board {
-    pub const GAME_BOARD_TOP: u16 = 5;
+    pub const GAME_BOARD_TOP: u16 = 2;
+    // cells up to this many rows or columns apart count as one object
+    pub const SEPARATION: i64 = 2;
+    pub const NEIGHBORHOOD: [(i64, i64); 8] = [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, -1),
+        (0, 1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+    ];
 
     pub fn new(
         width: u16,
         height: u16,
         init: Option<Vec<(usize, usize)>>,
         arg_offset: f32,
+        rule: Rule,
+        topology: Topology,
     ) -> Self {
-        let offset: Option<Position> = match arg_offset {
-            _ if arg_offset == 0.0 => None,
-            offset => {
-                let offset_row = (((offset / 100.0) * height as f32) as u16 % height - 2) as usize;
-                let offset_col = (((offset / 100.0) * width as f32) as u16 % width - 2) as usize;
-                Some((offset_row, offset_col).into())
-            }
-        };
-        let initial_life = if let Some(shape) = init {
-            Some(Shape::new(shape, offset).get_cells(width, height))
-        } else {
-            None
-        };
+        let offset = Board::offset(arg_offset, width, height);
+        let initial_life =
+            init.map(|shape| Shape::new(shape, offset).get_cells(&topology, width, height));
         let mut cells = vec![vec![Cell::Dead; width as usize]; height as usize];
         if let Some(init) = initial_life {
             init.into_iter()
@@ -126,78 +231,189 @@ impl Board {
             width,
             height,
             cells,
+            rule,
+            topology,
+        }
+    }
+
+    // where the starting pattern goes, arg_offset percent of the way across the board
+    pub fn offset(arg_offset: f32, width: u16, height: u16) -> Option<Position> {
+        match arg_offset {
+            _ if arg_offset == 0.0 => None,
+            offset => {
+                let offset_row = (((offset / 100.0) * height as f32) as u16 % height - 2) as usize;
+                let offset_col = (((offset / 100.0) * width as f32) as u16 % width - 2) as usize;
+                Some((offset_row, offset_col).into())
+            }
         }
     }
 
     fn count_living_neighbors(&self, pos: Position) -> u8 {
+        let (row, column) = (pos.row as i64, pos.column as i64);
+        let interior =
+            row > 0 && column > 0 && row + 1 < self.height as i64 && column + 1 < self.width as i64;
         let mut count = 0;
 
-        let row_up = if pos.row != 0 {
-            pos.row - 1
-        } else {
-            self.height as usize - 1
-        };
-        let row_down = if pos.row != self.height as usize - 1 {
-            pos.row + 1
-        } else {
-            0
-        };
-        let left_column = if pos.column != 0 {
-            pos.column - 1
-        } else {
-            self.width as usize - 1
-        };
-        let right_column = if pos.column != self.width as usize - 1 {
-            pos.column + 1
-        } else {
-            0
-        };
-        let neighbors = [
-            (row_up, left_column),
-            (row_up, pos.column),
-            (row_up, right_column),
-            (pos.row, left_column),
-            (pos.row, right_column),
-            (row_down, left_column),
-            (row_down, pos.column),
-            (row_down, right_column),
-        ];
-
-        for (neighbor_row, neighbor_column) in neighbors {
-            if self.cells[neighbor_row][neighbor_column] == Cell::Alive {
-                count += 1
+        for (row_step, column_step) in Board::NEIGHBORHOOD {
+            let (neighbor_row, neighbor_column) = (row + row_step, column + column_step);
+            let neighbor = match interior {
+                true => Some((neighbor_row as usize, neighbor_column as usize).into()),
+                false => {
+                    self.topology
+                        .locate(neighbor_row, neighbor_column, self.width, self.height)
+                }
+            };
+            // on twisted edges a neighbor can fold back onto the cell itself
+            match neighbor {
+                Some(n) if n != pos && self.cells[n.row][n.column] == Cell::Alive => count += 1,
+                _ => (),
             }
         }
         count
     }
 
+    pub fn grid(&self) -> GridSpec {
+        GridSpec {
+            topology: self.topology,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
     pub fn flip_cell(&mut self, pos: Position) {
         self.cells[pos.row][pos.column].flip();
     }
 
-    pub fn in_bounds(&self, row: u16, column: u16, term_rect: Rect) -> Result<Position, ()> {
-        let left = (term_rect.width - self.width * 2) / 2;
-        let right = left + self.width * 2;
-        let top = Board::GAME_BOARD_TOP;
-        let bottom = top + self.height;
-        match row < bottom && row >= top && column < right && column >= left {
-            true => Ok(Position {
-                row: (row - top) as usize,
-                column: (column - left) as usize / 2,
-            }),
-            false => Err(()),
-        }
+    pub fn contains(&self, pos: &Position) -> bool {
+        pos.row < self.height as usize && pos.column < self.width as usize
     }
 
     pub fn add_shape(&mut self, pos: Position, shape: Shape) {
         let mut positioned_shape = shape.clone();
         positioned_shape.offset = Some(pos);
         positioned_shape
-            .get_cells(self.width, self.height)
+            .get_cells(&self.topology, self.width, self.height)
             .into_iter()
             .for_each(|p| self.cells[p.row][p.column] = Cell::Alive);
     }
 
+    // living cells within (left, top, right, bottom), trimmed like to_shape
+    pub fn copy_area(&self, (left, top, right, bottom): (usize, usize, usize, usize)) -> Shape {
+        let cells = (top..=bottom)
+            .flat_map(|row| (left..=right).map(move |column| (row, column)))
+            .filter(|&(row, column)| self.cells[row][column] == Cell::Alive)
+            .map(|(row, column)| (row as i64, column as i64))
+            .collect();
+        Shape::normalized(cells)
+    }
+
+    // bring each cell within (left, top, right, bottom) to life with probability
+    // density and clear the rest
+    pub fn fill_area(
+        &mut self,
+        (left, top, right, bottom): (usize, usize, usize, usize),
+        density: f64,
+    ) {
+        for row in &mut self.cells[top..=bottom] {
+            for cell in &mut row[left..=right] {
+                *cell = match random::<f64>() < density {
+                    true => Cell::Alive,
+                    false => Cell::Dead,
+                };
+            }
+        }
+    }
+
+    pub fn population(&self) -> u64 {
+        self.cells
+            .iter()
+            .map(|row| row.iter().filter(|cell| **cell == Cell::Alive).count() as u64)
+            .sum()
+    }
+
+    // (left, top, right, bottom) of the cells that are not dead
+    pub fn bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bounds = None;
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (column, _) in cells.iter().enumerate().filter(|(_, c)| **c != Cell::Dead) {
+                bounds = Some(match bounds {
+                    None => (column, row, column, row),
+                    Some((left, top, right, bottom)) => (
+                        column.min(left),
+                        row.min(top),
+                        column.max(right),
+                        row.max(bottom),
+                    ),
+                });
+            }
+        }
+        bounds
+    }
+
+    // Groups of living cells within Board::SEPARATION of each other, which is as
+    // far apart as the cells of one object get. Cells are given as (row, column)
+    // relative to where the group was found, so objects crossing a wrapped edge
+    // keep their shape.
+    pub fn components(&self) -> Vec<Vec<(i64, i64)>> {
+        let reach = -Board::SEPARATION..=Board::SEPARATION;
+        let mut seen = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut components = Vec::new();
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if seen[row][column] || *cell != Cell::Alive {
+                    continue;
+                }
+                seen[row][column] = true;
+                let mut component = vec![(0, 0)];
+                let mut queue: Vec<(Position, _)> = vec![((row, column).into(), (0, 0))];
+                while let Some((pos, (r, c))) = queue.pop() {
+                    for dr in reach.clone() {
+                        for dc in reach.clone() {
+                            let (row, column) = (pos.row as i64 + dr, pos.column as i64 + dc);
+                            match self.topology.locate(row, column, self.width, self.height) {
+                                Some(n)
+                                    if !seen[n.row][n.column]
+                                        && self.cells[n.row][n.column] == Cell::Alive =>
+                                {
+                                    seen[n.row][n.column] = true;
+                                    component.push((r + dr, c + dc));
+                                    queue.push((n, (r + dr, c + dc)));
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+                components.push(component);
+            }
+        }
+        components
+    }
+
+    pub fn to_shape(&self) -> Shape {
+        let living: Vec<(usize, usize)> = self
+            .cells
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell == Cell::Alive)
+                    .map(move |(column, _)| (row, column))
+            })
+            .collect();
+        let top = living.iter().map(|cell| cell.0).min().unwrap_or(0);
+        let left = living.iter().map(|cell| cell.1).min().unwrap_or(0);
+        Shape::new(
+            living
+                .into_iter()
+                .map(|(row, column)| (row - top, column - left))
+                .collect(),
+            None,
+        )
+    }
+
     pub fn randomize(&mut self) {
         for row in &mut self.cells {
             for cell in row {
@@ -214,37 +430,368 @@ impl Board {
         }
     }
 
-    pub fn tick(&mut self) {
-        let mut new_cells = self.cells.clone();
-
-        (0..self.height as usize).into_iter().for_each(|row| {
-            (0..self.width as usize).into_iter().for_each(|column| {
+    // compute the next generation of the given rows, starting at first_row, into rows
+    fn next_band(&self, first_row: usize, rows: &mut [Vec<Cell>]) {
+        for (row, new_cells) in (first_row..).zip(rows.iter_mut()) {
+            for (column, new_cell) in new_cells.iter_mut().enumerate() {
                 match (
                     self.cells[row][column],
-                    self.count_living_neighbors(Position { row, column }),
+                    self.count_living_neighbors(Position { row, column }) as usize,
                 ) {
-                    // Game of Life change of cell state conditions
-                    (Cell::Dead, 3) => new_cells[row][column] = Cell::Alive,
-                    (Cell::Alive, n) if n > 3 => new_cells[row][column] = Cell::Dead,
-                    (Cell::Alive, n) if n < 2 => new_cells[row][column] = Cell::Dead,
+                    // change of cell state conditions given by the B/S rule
+                    (Cell::Dead, n) if self.rule.birth[n] => *new_cell = Cell::Alive,
+                    (Cell::Alive, n) if !self.rule.survival[n] => {
+                        *new_cell = Cell::decay(0, self.rule.states)
+                    }
+                    (Cell::Dying(age), _) => *new_cell = Cell::decay(age, self.rule.states),
                     _ => (),
                 }
-            });
-        });
+            }
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    pub fn tick(&mut self) {
+        let mut new_cells = self.cells.clone();
+        self.next_band(0, &mut new_cells);
+        self.cells = new_cells;
+    }
+
+    // Each worker thread fills one band of rows. Bands only write their own rows and
+    // read the previous generation, so rows wrapping around band edges need no syncing.
+    #[cfg(feature = "parallel")]
+    pub fn tick(&mut self) {
+        use rayon::prelude::*;
+
+        let mut new_cells = self.cells.clone();
+        let band_height = new_cells
+            .len()
+            .div_ceil(rayon::current_num_threads())
+            .max(1);
+        new_cells
+            .par_chunks_mut(band_height)
+            .enumerate()
+            .for_each(|(band, rows)| self.next_band(band * band_height, rows));
         self.cells = new_cells;
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EngineKind {
+    Dense,
+    BitPacked,
+    HashLife,
+    Sparse,
+}
+
+impl EngineKind {
+    // engines that run on the unbounded plane, using the board as a window
+    pub fn is_unbounded(&self) -> bool {
+        matches!(self, EngineKind::HashLife | EngineKind::Sparse)
+    }
+}
+
+impl FromStr for EngineKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dense" => Ok(EngineKind::Dense),
+            "bitpacked" => Ok(EngineKind::BitPacked),
+            "hashlife" => Ok(EngineKind::HashLife),
+            "sparse" => Ok(EngineKind::Sparse),
+            _ => Err(format!(
+                "unknown engine '{}', expected dense, bitpacked, hashlife or sparse",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EngineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineKind::Dense => write!(f, "dense"),
+            EngineKind::BitPacked => write!(f, "bitpacked"),
+            EngineKind::HashLife => write!(f, "hashlife"),
+            EngineKind::Sparse => write!(f, "sparse"),
+        }
+    }
+}
+
+pub enum UnboundedUniverse {
+    HashLife(Box<hashlife::Universe>),
+    Sparse(sparse::Universe),
+}
+
+impl UnboundedUniverse {
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        match self {
+            UnboundedUniverse::HashLife(universe) => universe.set_cell(x, y, cell == Cell::Alive),
+            UnboundedUniverse::Sparse(universe) => universe.set_cell(x, y, cell),
+        }
+    }
+
+    // on failure, the number of generations advanced before stopping and why
+    fn advance(&mut self, generations: u64) -> Result<(), (u64, String)> {
+        match self {
+            UnboundedUniverse::HashLife(universe) => {
+                let start = universe.generation;
+                universe
+                    .advance(generations)
+                    .map_err(|reason| (universe.generation - start, reason))
+            }
+            UnboundedUniverse::Sparse(universe) => {
+                universe.advance(generations);
+                Ok(())
+            }
+        }
+    }
+
+    fn window(&self, left: i64, top: i64, width: usize, height: usize) -> Vec<Vec<Cell>> {
+        match self {
+            UnboundedUniverse::HashLife(universe) => universe
+                .window(left, top, width, height)
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|alive| if alive { Cell::Alive } else { Cell::Dead })
+                        .collect()
+                })
+                .collect(),
+            UnboundedUniverse::Sparse(universe) => universe.window(left, top, width, height),
+        }
+    }
+
+    fn population(&self) -> u64 {
+        match self {
+            UnboundedUniverse::HashLife(universe) => universe.population(),
+            UnboundedUniverse::Sparse(universe) => universe.population(),
+        }
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        match self {
+            UnboundedUniverse::HashLife(universe) => universe.bounds(),
+            UnboundedUniverse::Sparse(universe) => universe.bounds(),
+        }
+    }
+}
+
+// What dragging the mouse does to the cells it passes over
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Brush {
+    Draw,
+    Erase,
+    #[default]
+    Toggle,
+}
+
+impl Brush {
+    pub fn next(&self) -> Self {
+        match self {
+            Brush::Draw => Brush::Erase,
+            Brush::Erase => Brush::Toggle,
+            Brush::Toggle => Brush::Draw,
+        }
+    }
+}
+
+impl fmt::Display for Brush {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Brush::Draw => write!(f, "draw"),
+            Brush::Erase => write!(f, "erase"),
+            Brush::Toggle => write!(f, "toggle"),
+        }
+    }
+}
+
+// A mouse stroke in progress: the cell it leaves behind, the last cell it reached
+// and the board from before it started
+struct Stroke {
+    paint: Cell,
+    last: Position,
+    before: Vec<Vec<Cell>>,
+}
+
+// A rectangle of the board between the cell it was started on and the opposite
+// corner, which can lie in any direction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selection {
+    pub anchor: Position,
+    pub corner: Position,
+}
+
+impl Selection {
+    // (left, top, right, bottom)
+    pub fn bounds(&self) -> (usize, usize, usize, usize) {
+        let (anchor, corner) = (&self.anchor, &self.corner);
+        (
+            anchor.column.min(corner.column),
+            anchor.row.min(corner.row),
+            anchor.column.max(corner.column),
+            anchor.row.max(corner.row),
+        )
+    }
+}
+
+// cells on a straight line between two cells, both included
+fn line(from: &Position, to: &Position) -> Vec<Position> {
+    let (from_row, from_column) = (from.row as i64, from.column as i64);
+    let (rows, columns) = (to.row as i64 - from_row, to.column as i64 - from_column);
+    let steps = rows.abs().max(columns.abs());
+    (0..=steps)
+        .map(|step| {
+            // distance * step / steps, rounded to the nearest cell
+            let along = |distance: i64| match steps {
+                0 => 0,
+                _ => (2 * distance * step + steps).div_euclid(2 * steps),
+            };
+            let row = from_row + along(rows);
+            let column = from_column + along(columns);
+            (row as usize, column as usize).into()
+        })
+        .collect()
+}
+
+pub enum Engine {
+    Dense,
+    // The packed board is what gets stepped, and is only unpacked onto the board
+    // afterwards. Edits made on the board are packed again when synced.
+    BitPacked(BitBoard),
+    // The board is a window onto an unbounded universe with its top left cell at
+    // origin (x, y). The board contents as of the last sync are kept so that edits
+    // made on the board can be carried over.
+    Unbounded {
+        universe: UnboundedUniverse,
+        origin: (i64, i64),
+        synced: Vec<Vec<Cell>>,
+    },
+}
+
+impl Engine {
+    pub fn new(kind: EngineKind, board: &Board) -> Result<Self, String> {
+        let unbounded = board.topology == Topology::Unbounded;
+        match kind {
+            _ if kind.is_unbounded() != unbounded => Err(format!(
+                "the {} engine cannot use {}, only hashlife and sparse run on the unbounded plane",
+                kind,
+                board.grid()
+            )),
+            EngineKind::Dense => Ok(Engine::Dense),
+            EngineKind::BitPacked if !BitBoard::supports(&board.rule, &board.topology) => {
+                Err(format!(
+                    "the bitpacked engine cannot run {} on {}, it needs a two-state rule on an unshifted torus",
+                    board.rule,
+                    board.grid()
+                ))
+            }
+            EngineKind::BitPacked => Ok(Engine::BitPacked(BitBoard::from_board(board))),
+            EngineKind::HashLife if !hashlife::Universe::supports(&board.rule) => Err(format!(
+                "the hashlife engine cannot run {}, it needs a two-state rule without B0",
+                board.rule
+            )),
+            EngineKind::Sparse if !sparse::Universe::supports(&board.rule) => Err(format!(
+                "the sparse engine cannot run {}, it needs a rule without B0",
+                board.rule
+            )),
+            EngineKind::HashLife | EngineKind::Sparse => Ok(Engine::Unbounded {
+                universe: match kind {
+                    EngineKind::HashLife => {
+                        UnboundedUniverse::HashLife(Box::new(hashlife::Universe::new(board.rule)))
+                    }
+                    _ => UnboundedUniverse::Sparse(sparse::Universe::new(board.rule)),
+                },
+                origin: (0, 0),
+                synced: vec![vec![Cell::Dead; board.width as usize]; board.height as usize],
+            }),
+        }
+    }
+}
+
+impl Engine {
+    // bring a starting pattern to life on the unbounded plane, placed where it would
+    // go on the board but not clipped to the window
+    pub fn seed(&mut self, shape: &Shape) {
+        let Engine::Unbounded {
+            universe,
+            origin: (left, top),
+            ..
+        } = self
+        else {
+            return;
+        };
+        let (row_offset, column_offset) = shape
+            .offset
+            .as_ref()
+            .map_or((0, 0), |point| (point.row as i64, point.column as i64));
+        for pos in &shape.pattern {
+            let x = *left + column_offset + pos.column as i64;
+            let y = *top + row_offset + pos.row as i64;
+            universe.set_cell(x, y, Cell::Alive);
+        }
+    }
+}
+
 pub struct GolState {
     pub game_board: Board,
+    pub engine: Engine,
     pub paused: bool,
+    // generations per second while running, and how many 'N' jumps ahead
+    pub speed: f64,
+    pub step_size: u64,
+    // generations owed to the running simulation but not yet computed
+    pending: f64,
     pub term_rect: Rect,
+    pub viewport: Viewport,
+    pub save_path: PathBuf,
+    pub message: Option<String>,
+    pub generation: u64,
+    pub timeline: Timeline,
+    // board cell edited from the keyboard, while cursor mode is on
+    pub cursor: Option<Position>,
+    pub brush: Brush,
+    stroke: Option<Stroke>,
+    pub selection: Option<Selection>,
+    // the selection corner follows the cursor, or the mouse while dragging
+    marking: bool,
+    selecting: bool,
+    pub clipboard: Option<Shape>,
+    // board cell the mouse was last over, and the terminal (row, column) it was at
+    pub hover: Option<Position>,
+    mouse: Option<(u16, u16)>,
+    // chance of each cell coming alive when the selection is filled
+    pub density: f64,
+    pub populations: PopulationHistory,
+    pub chart_window: ChartWindow,
+    pub show_census: bool,
+    // every control listed over the game
+    pub show_help: bool,
+    cycles: CycleDetector,
+    census: Option<Census>,
+    history: History,
+    // without a UI, steps skip the undo history, timeline and population chart
+    headless: bool,
+    // births, deaths and the generations they were counted over in the last step
+    last_step: (u64, u64, u64),
+    rate: RateMeter,
     shape_presets: [Shape; 6],
     preset_index: usize,
 }
 
 impl GolState {
-    pub fn new(game_board: Board, term_rect: Rect) -> Self {
+    pub const DEFAULT_SPEED: f64 = 15.0;
+    pub const DEFAULT_STEP_SIZE: u64 = 100;
+    pub const DEFAULT_DENSITY: f64 = 0.5;
+    const SPEEDS: [f64; 14] = [
+        1.0, 2.0, 5.0, 10.0, 15.0, 30.0, 60.0, 120.0, 250.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0,
+    ];
+    // unbounded patterns larger than this many cells are not copied out to check
+    // for cycles, take a census or export
+    const MAX_PATTERN_AREA: i64 = 1 << 20;
+
+    pub fn new(game_board: Board, engine: Engine, term_rect: Rect, save_path: PathBuf) -> Self {
         let paused = true;
         let preset_index = 0;
         let shape_presets = [
@@ -255,26 +802,801 @@ impl GolState {
             Shape::new(Shape::B_HEPTOMINO.to_vec(), None),
             Shape::new(Shape::THUNDERBIRD.to_vec(), None),
         ];
-        GolState {
+        let mut game_state = GolState {
             game_board,
+            engine,
             paused,
+            speed: GolState::DEFAULT_SPEED,
+            step_size: GolState::DEFAULT_STEP_SIZE,
+            pending: 0.0,
             term_rect,
+            viewport: Viewport::default(),
+            save_path,
+            message: None,
+            generation: 0,
+            timeline: Timeline::default(),
+            cursor: None,
+            brush: Brush::Toggle,
+            stroke: None,
+            selection: None,
+            marking: false,
+            selecting: false,
+            clipboard: None,
+            hover: None,
+            mouse: None,
+            density: GolState::DEFAULT_DENSITY,
+            populations: PopulationHistory::default(),
+            chart_window: ChartWindow::Last100,
+            show_census: false,
+            show_help: false,
+            cycles: CycleDetector::default(),
+            census: None,
+            history: History::default(),
+            headless: false,
+            last_step: (0, 0, 0),
+            rate: RateMeter::default(),
             preset_index,
             shape_presets,
+        };
+        // the engine starts out from the board, and shows what was seeded beyond it
+        game_state.sync_window();
+        game_state.load_window();
+        game_state.timeline.record(0, &game_state.game_board.cells);
+        game_state.record_generation();
+        game_state
+    }
+
+    // a game run without the UI, which keeps only what the final report needs
+    pub fn headless(game_board: Board, engine: Engine) -> Self {
+        GolState {
+            headless: true,
+            ..GolState::new(game_board, engine, Rect::default(), PathBuf::new())
+        }
+    }
+
+    // the cycle found by the current generation
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle(self.generation)
+    }
+
+    pub fn advance(&mut self, mut generations: u64) {
+        // a stroke carries on over the new generation
+        let stroke = self.stroke.take();
+        if let Some(stroke) = &stroke {
+            self.commit_edit(&stroke.before);
+        }
+        let before = self.game_board.cells.clone();
+        let (start, keep) = (self.generation, !self.headless);
+        let board = &mut self.game_board;
+        let (timeline, populations) = (&mut self.timeline, &mut self.populations);
+        // every generation of a bounded board goes on the timeline and the chart, so
+        // that it can be stepped back to and none is skipped over
+        match &mut self.engine {
+            Engine::Dense => {
+                for step in 1..=generations {
+                    board.tick();
+                    if keep {
+                        timeline.record(start + step, &board.cells);
+                        populations.record(start + step, board.population());
+                    }
+                }
+            }
+            Engine::BitPacked(packed) => {
+                for step in 1..=generations {
+                    packed.step(&board.rule);
+                    if keep {
+                        packed.write_to(board);
+                        timeline.record(start + step, &board.cells);
+                        populations.record(start + step, board.population());
+                    }
+                }
+            }
+            Engine::Unbounded { universe, .. } => {
+                if let Err((done, reason)) = universe.advance(generations) {
+                    generations = done;
+                    self.paused = true;
+                    self.message = Some(format!("Stopped: {}", reason));
+                }
+            }
+        }
+        self.load_window();
+        let generation = self.generation + generations;
+        if !self.headless {
+            match &self.engine {
+                // undoing would only take the window back, leaving the rest of the
+                // universe ahead, so a step clears the history as panning does
+                Engine::Unbounded { .. } => {
+                    self.history.clear();
+                    self.timeline.record(generation, &self.game_board.cells);
+                }
+                _ => self.history.record(
+                    &before,
+                    &self.game_board.cells,
+                    (self.generation, generation),
+                ),
+            }
+            self.rate.record(Instant::now(), generations);
+        }
+        self.generation = generation;
+
+        let mut changes = (0, 0, generations);
+        for (old, new) in before
+            .iter()
+            .flatten()
+            .zip(self.game_board.cells.iter().flatten())
+        {
+            match (*old == Cell::Alive, *new == Cell::Alive) {
+                (false, true) => changes.0 += 1,
+                (true, false) => changes.1 += 1,
+                _ => (),
+            }
+        }
+        self.last_step = changes;
+        self.record_generation();
+        self.stroke = stroke.map(|stroke| Stroke {
+            before: self.game_board.cells.clone(),
+            ..stroke
+        });
+    }
+
+    // note the population and state of the current generation for the chart and
+    // cycle detection
+    fn record_generation(&mut self) {
+        if !self.headless {
+            let population = self.stats().population;
+            self.populations.record(self.generation, population);
+        }
+
+        let (fingerprint, corner) = match &self.engine {
+            Engine::Unbounded { universe, .. } => match universe.bounds() {
+                Some((left, top, right, bottom)) => {
+                    let (width, height) = (right - left + 1, bottom - top + 1);
+                    // the product alone overflows once gliders have flown far apart
+                    match width.checked_mul(height) {
+                        Some(area) if area <= GolState::MAX_PATTERN_AREA => (),
+                        _ => return,
+                    }
+                    let cells = universe.window(left, top, width as usize, height as usize);
+                    let bounds = (0, 0, width as usize - 1, height as usize - 1);
+                    (cycle::fingerprint(&cells, Some(bounds)), (left, top))
+                }
+                None => (cycle::fingerprint(&[], None), (0, 0)),
+            },
+            _ => {
+                let bounds = self.game_board.bounds();
+                let corner = bounds.map_or((0, 0), |(left, top, ..)| (left as i64, top as i64));
+                (cycle::fingerprint(&self.game_board.cells, bounds), corner)
+            }
+        };
+        self.cycles.record(self.generation, fingerprint, corner);
+        self.refresh_census();
+    }
+
+    // keep the census up to date while it is shown
+    fn refresh_census(&mut self) {
+        self.census = match self.show_census {
+            true => self.take_census(),
+            false => None,
+        };
+    }
+
+    // The board, or on unbounded engines the whole universe copied out onto a
+    // bounded plane if it is small enough
+    pub fn whole_board(&self) -> Option<Cow<'_, Board>> {
+        let Engine::Unbounded { universe, .. } = &self.engine else {
+            return Some(Cow::Borrowed(&self.game_board));
+        };
+        let (left, top, right, bottom) = universe.bounds().unwrap_or((0, 0, -1, -1));
+        let (width, height) = (right - left + 1, bottom - top + 1);
+        let area = width.checked_mul(height);
+        if area.is_none_or(|area| area > GolState::MAX_PATTERN_AREA)
+            || width > u16::MAX as i64
+            || height > u16::MAX as i64
+        {
+            return None;
+        }
+        let rule = self.game_board.rule;
+        let mut board = Board::new(
+            width as u16,
+            height as u16,
+            None,
+            0.0,
+            rule,
+            Topology::Plane,
+        );
+        board.cells = universe.window(left, top, width as usize, height as usize);
+        Some(Cow::Owned(board))
+    }
+
+    pub fn take_census(&self) -> Option<Census> {
+        self.whole_board().map(|board| Census::of(&board))
+    }
+
+    // census of the current generation while the census is shown
+    pub fn census(&self) -> Option<&Census> {
+        self.census.as_ref()
+    }
+
+    pub fn toggle_census(&mut self) {
+        self.show_census = !self.show_census;
+        self.refresh_census();
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    pub fn cycle_chart_window(&mut self) {
+        self.chart_window = self.chart_window.next();
+    }
+
+    // population and bounds cover the whole universe on unbounded engines, while
+    // births and deaths are counted on the board
+    pub fn stats(&self) -> Stats {
+        let board = &self.game_board;
+        let (population, bounds) = match &self.engine {
+            Engine::Unbounded { universe, .. } => (universe.population(), universe.bounds()),
+            _ => (
+                board.population(),
+                board.bounds().map(|(left, top, right, bottom)| {
+                    (left as i64, top as i64, right as i64, bottom as i64)
+                }),
+            ),
+        };
+        let (left, top) = match &self.engine {
+            Engine::Unbounded { origin, .. } => *origin,
+            _ => (0, 0),
+        };
+        let cursor = self
+            .cursor
+            .as_ref()
+            .map(|pos| (left + pos.column as i64, top + pos.row as i64));
+        Stats {
+            generation: self.generation,
+            speed: self.speed,
+            population,
+            births: self.last_step.0,
+            deaths: self.last_step.1,
+            step: self.last_step.2,
+            bounds,
+            rate: self.rate.rate(Instant::now()),
+            cycle: self.cycle(),
+            cursor,
+            brush: self.brush,
+            selection: self.selection.as_ref().map(|selection| {
+                let (left, top, right, bottom) = selection.bounds();
+                (right - left + 1, bottom - top + 1)
+            }),
+            clipboard: self.clipboard.as_ref().map(|clipboard| {
+                let (rows, columns) = clipboard.size();
+                (columns, rows)
+            }),
         }
     }
 
+    // change the board in a way that can be undone
+    pub fn edit(&mut self, edit: impl FnOnce(&mut Board)) {
+        self.end_stroke();
+        let before = self.game_board.cells.clone();
+        edit(&mut self.game_board);
+        self.commit_edit(&before);
+    }
+
+    fn commit_edit(&mut self, before: &[Vec<Cell>]) {
+        let generations = (self.generation, self.generation);
+        self.history
+            .record(before, &self.game_board.cells, generations);
+        self.timeline
+            .record(self.generation, &self.game_board.cells);
+        self.sync_window();
+        self.record_generation();
+    }
+
+    // Start painting at pos with the brush, or erasing whatever the brush. Strokes
+    // paint the whole block of cells a character shows, with pos its top left cell.
+    // A toggling stroke draws or erases depending on whether that block shows life.
+    pub fn begin_stroke(&mut self, pos: Position, erase: bool) {
+        self.end_stroke();
+        let block = self.block(&pos);
+        let paint = match (erase, self.brush) {
+            (true, _) | (false, Brush::Erase) => Cell::Dead,
+            (false, Brush::Draw) => Cell::Alive,
+            (false, Brush::Toggle) => match block
+                .iter()
+                .any(|cell| self.game_board.cells[cell.row][cell.column] == Cell::Alive)
+            {
+                true => Cell::Dead,
+                false => Cell::Alive,
+            },
+        };
+        let before = self.game_board.cells.clone();
+        for cell in block {
+            self.game_board.cells[cell.row][cell.column] = paint;
+        }
+        self.sync_window();
+        self.stroke = Some(Stroke {
+            paint,
+            last: pos,
+            before,
+        });
+    }
+
+    // paint every character on the way from where the stroke last reached to pos
+    pub fn continue_stroke(&mut self, pos: Position) {
+        let Some(stroke) = &mut self.stroke else {
+            return;
+        };
+        let (paint, last) = (
+            stroke.paint,
+            std::mem::replace(&mut stroke.last, pos.clone()),
+        );
+        let (columns, rows) = self.viewport.char_cells();
+        let (top, left) = (self.viewport.top, self.viewport.left);
+        let to_char = |pos: &Position| -> Position {
+            let row = pos.row.saturating_sub(top) / rows;
+            (row, pos.column.saturating_sub(left) / columns).into()
+        };
+        for char in line(&to_char(&last), &to_char(&pos)) {
+            let corner = (top + char.row * rows, left + char.column * columns).into();
+            for cell in self.block(&corner) {
+                self.game_board.cells[cell.row][cell.column] = paint;
+            }
+        }
+        self.sync_window();
+    }
+
+    // the cells on the board drawn by the character whose top left cell is pos
+    fn block(&self, pos: &Position) -> Vec<Position> {
+        let (columns, rows) = self.viewport.char_cells();
+        (pos.row..pos.row + rows)
+            .flat_map(|row| (pos.column..pos.column + columns).map(move |column| (row, column)))
+            .map(Position::from)
+            .filter(|cell| self.game_board.contains(cell))
+            .collect()
+    }
+
+    // the whole stroke is undone in one go
+    pub fn end_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take() {
+            self.commit_edit(&stroke.before);
+        }
+    }
+
+    pub fn painting(&self) -> bool {
+        self.stroke.is_some()
+    }
+
+    pub fn cycle_brush(&mut self) {
+        self.brush = self.brush.next();
+    }
+
+    pub fn undo(&mut self) {
+        self.end_stroke();
+        if self
+            .history
+            .undo(&mut self.game_board.cells, &mut self.generation)
+        {
+            self.timeline
+                .record(self.generation, &self.game_board.cells);
+            self.sync_window();
+            self.record_generation();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        self.end_stroke();
+        if self
+            .history
+            .redo(&mut self.game_board.cells, &mut self.generation)
+        {
+            self.timeline
+                .record(self.generation, &self.game_board.cells);
+            self.sync_window();
+            self.record_generation();
+        }
+    }
+
+    // show a stored generation, which can be undone like an edit
+    pub fn show_frame(&mut self, index: usize) {
+        self.end_stroke();
+        let (Some(cells), Some(generation)) =
+            (self.timeline.frame(index), self.timeline.generation(index))
+        else {
+            return;
+        };
+        if cells.len() != self.game_board.cells.len() {
+            return;
+        }
+        // only the window is stored, so an unbounded universe can't go back to it
+        // without losing whatever lies outside
+        if matches!(self.engine, Engine::Unbounded { .. }) && generation != self.generation {
+            self.message = Some(String::from(
+                "Can't rewind an unbounded universe: only the window is stored",
+            ));
+            return;
+        }
+        let generations = (self.generation, generation);
+        self.history
+            .record(&self.game_board.cells, &cells, generations);
+        self.game_board.cells = cells;
+        self.generation = generation;
+        self.sync_window();
+        self.refresh_census();
+    }
+
+    pub fn step_back(&mut self) {
+        let Some(index) = self.timeline.index_of(self.generation) else {
+            return;
+        };
+        match self.timeline.generation(index) == Some(self.generation) {
+            true if index > 0 => self.show_frame(index - 1),
+            true => (),
+            false => self.show_frame(index),
+        }
+    }
+
+    // replay a stored later generation if there is one, otherwise compute it
+    pub fn step_forward(&mut self) {
+        let next = self.timeline.index_of(self.generation).map_or(0, |i| i + 1);
+        match next < self.timeline.len() {
+            true => self.show_frame(next),
+            false => self.advance(1),
+        }
+    }
+
+    // carry cells edited on the board over to the engine
+    fn sync_window(&mut self) {
+        match &mut self.engine {
+            Engine::Dense => (),
+            Engine::BitPacked(packed) => *packed = BitBoard::from_board(&self.game_board),
+            Engine::Unbounded {
+                universe,
+                origin: (left, top),
+                synced,
+            } => {
+                for (row, (cells, synced_cells)) in self
+                    .game_board
+                    .cells
+                    .iter()
+                    .zip(synced.iter_mut())
+                    .enumerate()
+                {
+                    for (column, (cell, synced_cell)) in cells.iter().zip(synced_cells).enumerate()
+                    {
+                        if cell != synced_cell {
+                            universe.set_cell(*left + column as i64, *top + row as i64, *cell);
+                            *synced_cell = *cell;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // redraw the board from the engine, or on unbounded engines from the part of
+    // the universe under the window
+    fn load_window(&mut self) {
+        let board = &mut self.game_board;
+        match &mut self.engine {
+            Engine::Dense => (),
+            Engine::BitPacked(packed) => packed.write_to(board),
+            Engine::Unbounded {
+                universe,
+                origin: (left, top),
+                synced,
+            } => {
+                board.cells =
+                    universe.window(*left, *top, board.width as usize, board.height as usize);
+                *synced = board.cells.clone();
+            }
+        }
+    }
+
+    // move the view by rows x columns characters, sliding the window over the
+    // unbounded plane or scrolling the viewport over a bounded board
+    pub fn pan(&mut self, rows: i64, columns: i64) {
+        self.end_stroke();
+        let (char_columns, char_rows) = self.viewport.char_cells();
+        self.sync_window();
+        match &mut self.engine {
+            // the board now shows other cells, which past changes no longer match
+            Engine::Unbounded { origin, .. } => {
+                *origin = (
+                    origin.0 + columns * char_columns as i64,
+                    origin.1 + rows * char_rows as i64,
+                );
+                self.history.clear();
+            }
+            _ => self.viewport.scroll(rows, columns),
+        }
+        self.load_window();
+        self.fit_viewport();
+    }
+
+    pub fn zoom(&mut self, zoom_in: bool) {
+        match zoom_in {
+            true => self.viewport.zoom_in(),
+            false => self.viewport.zoom_out(),
+        }
+        self.fit_viewport();
+    }
+
+    pub fn cycle_render_mode(&mut self) {
+        self.viewport.cycle_mode();
+        self.fit_viewport();
+    }
+
+    pub fn resize(&mut self, term_rect: Rect) {
+        self.term_rect = term_rect;
+        self.fit_viewport();
+    }
+
+    // clamp the viewport to the board as shown in the current terminal
+    pub fn fit_viewport(&mut self) {
+        let area = GolUi::new(self.term_rect, self).game_area;
+        let board = &self.game_board;
+        self.viewport.fit(board.width, board.height, area);
+        // the mouse may be over another cell now
+        if let Some((row, column)) = self.mouse {
+            self.hover = self.cell_under(row, column);
+        }
+    }
+
+    // board cell under a character of the terminal, taking the viewport into
+    // account, which is the top left of the cells it shows
+    pub fn cell_under(&self, row: u16, column: u16) -> Option<Position> {
+        let area = GolUi::new(self.term_rect, self).game_area;
+        self.viewport
+            .cell_at(area, row, column)
+            .filter(|position| self.game_board.contains(position))
+    }
+
+    pub fn move_mouse(&mut self, row: u16, column: u16) {
+        self.mouse = Some((row, column));
+        self.hover = self.cell_under(row, column);
+    }
+
+    // move the view over the middle of the living cells
+    pub fn center_view(&mut self) {
+        self.end_stroke();
+        self.sync_window();
+        let area = GolUi::new(self.term_rect, self).game_area;
+        let (columns, rows) = self.viewport.visible_cells(area);
+        let (columns, rows) = (columns as i64, rows as i64);
+        let board = &self.game_board;
+        match &mut self.engine {
+            Engine::Unbounded {
+                universe, origin, ..
+            } => {
+                let (width, height) = (board.width as i64, board.height as i64);
+                if let Some((left, top, right, bottom)) = universe.bounds() {
+                    *origin = (
+                        (left + right - width) / 2 + 1,
+                        (top + bottom - height) / 2 + 1,
+                    );
+                    self.history.clear();
+                }
+            }
+            _ => {
+                if let Some((left, top, right, bottom)) = board.bounds() {
+                    let start = |low: usize, high: usize, shown: i64| {
+                        ((low + high) as i64 / 2 - shown / 2).max(0) as usize
+                    };
+                    self.viewport.top = start(top, bottom, rows);
+                    self.viewport.left = start(left, right, columns);
+                }
+            }
+        }
+        self.load_window();
+        self.fit_viewport();
+    }
+
+    // advance as many generations as the speed allows in elapsed wall time
+    // Stepping stops at deadline so that input and drawing keep up, and whatever is
+    // still owed then is dropped. Steps double in size while there is time left.
+    pub fn run(&mut self, elapsed: Duration, deadline: Instant) {
+        if self.paused {
+            self.pending = 0.0;
+            return;
+        }
+        self.pending += elapsed.as_secs_f64() * self.speed;
+        let mut step = 1.0;
+        while self.pending >= 1.0 {
+            let generations = self.pending.floor().min(step);
+            self.pending -= generations;
+            self.advance(generations as u64);
+            if self.paused || Instant::now() >= deadline {
+                self.pending = 0.0;
+            }
+            step *= 2.0;
+        }
+    }
+
+    pub fn change_speed(&mut self, faster: bool) {
+        let speeds = GolState::SPEEDS;
+        self.speed = match faster {
+            true => speeds.into_iter().find(|&s| s > self.speed),
+            false => speeds.into_iter().rev().find(|&s| s < self.speed),
+        }
+        .unwrap_or(self.speed);
+    }
+
     pub fn toggle_playpause(&mut self) {
         self.paused = !self.paused;
     }
 
+    // start the cursor in the middle of the view, or put it away
+    pub fn toggle_cursor(&mut self) {
+        self.marking = false;
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => {
+                let area = GolUi::new(self.term_rect, self).game_area;
+                let (columns, rows) = self.viewport.visible_cells(area);
+                let board = &self.game_board;
+                let row = (self.viewport.top + rows / 2).min(board.height as usize - 1);
+                let column = (self.viewport.left + columns / 2).min(board.width as usize - 1);
+                Some((row, column).into())
+            }
+        };
+    }
+
+    // move the cursor within the board, scrolling the view to keep it in sight
+    pub fn move_cursor(&mut self, rows: i64, columns: i64) {
+        let Some(cursor) = &self.cursor else {
+            return;
+        };
+        let board = &self.game_board;
+        let row = (cursor.row as i64 + rows).clamp(0, board.height as i64 - 1);
+        let column = (cursor.column as i64 + columns).clamp(0, board.width as i64 - 1);
+        let cursor = Position::from((row as usize, column as usize));
+        let area = GolUi::new(self.term_rect, self).game_area;
+        self.viewport.reveal(&cursor, area);
+        if let Some(selection) = self.selection.as_mut().filter(|_| self.marking) {
+            selection.corner = cursor.clone();
+        }
+        self.cursor = Some(cursor);
+    }
+
+    pub fn toggle_at_cursor(&mut self) {
+        if let Some(cursor) = self.cursor.clone() {
+            self.edit(|board| board.flip_cell(cursor));
+        }
+    }
+
+    pub fn stamp_at_cursor(&mut self) {
+        if let Some(cursor) = self.cursor.clone() {
+            let shape = self.current_shape();
+            self.edit(|board| board.add_shape(cursor, shape));
+        }
+    }
+
+    // start selecting from the cursor, growing the selection as the cursor moves,
+    // or drop the selection
+    pub fn toggle_selection(&mut self) {
+        self.marking = false;
+        if self.selection.take().is_some() {
+            return;
+        }
+        if self.cursor.is_none() {
+            self.toggle_cursor();
+        }
+        if let Some(cursor) = self.cursor.clone() {
+            self.selection = Some(Selection {
+                anchor: cursor.clone(),
+                corner: cursor,
+            });
+            self.marking = true;
+        }
+    }
+
+    pub fn begin_selection(&mut self, pos: Position) {
+        self.end_stroke();
+        self.marking = false;
+        self.selection = Some(Selection {
+            anchor: pos.clone(),
+            corner: pos,
+        });
+        self.selecting = true;
+    }
+
+    pub fn extend_selection(&mut self, pos: Position) {
+        if let Some(selection) = self.selection.as_mut().filter(|_| self.selecting) {
+            selection.corner = pos;
+        }
+    }
+
+    pub fn end_selection(&mut self) {
+        self.selecting = false;
+    }
+
+    pub fn selecting(&self) -> bool {
+        self.selecting
+    }
+
+    pub fn copy_selection(&mut self) {
+        if let Some(selection) = &self.selection {
+            self.clipboard = Some(self.game_board.copy_area(selection.bounds()));
+        }
+    }
+
+    pub fn cut_selection(&mut self) {
+        if let Some(bounds) = self.selection.as_ref().map(Selection::bounds) {
+            self.copy_selection();
+            self.edit(|board| board.fill_area(bounds, 0.0));
+        }
+    }
+
+    // add the clipboard with its top left corner at the cursor, or under the mouse
+    // outside cursor mode
+    pub fn paste(&mut self) {
+        let target = self.cursor.clone().or_else(|| self.hover.clone());
+        if let (Some(shape), Some(pos)) = (self.clipboard.clone(), target) {
+            self.edit(|board| board.add_shape(pos, shape));
+        }
+    }
+
+    pub fn fill_selection(&mut self) {
+        if let Some(bounds) = self.selection.as_ref().map(Selection::bounds) {
+            let density = self.density;
+            self.edit(|board| board.fill_area(bounds, density));
+        }
+    }
+
+    // turn or mirror the shape in hand, which presets keep when cycled past
+    pub fn transform_shape(&mut self, transform: Transform) {
+        match &mut self.clipboard {
+            Some(clipboard) => clipboard.transform(transform),
+            None => self.shape_presets[self.preset_index].transform(transform),
+        }
+    }
+
+    // put the clipboard down and go back to the presets, or move on to the next one
     pub fn cycle_presets(&mut self) {
-        self.preset_index = (self.preset_index + 1) % self.shape_presets.len();
+        if self.clipboard.take().is_none() {
+            self.preset_index = (self.preset_index + 1) % self.shape_presets.len();
+        }
     }
 
     pub fn current_preset(&self) -> Shape {
         self.shape_presets[self.preset_index].clone()
     }
+
+    // what stamping adds: the clipboard while one is held, otherwise the preset
+    pub fn current_shape(&self) -> Shape {
+        self.clipboard
+            .clone()
+            .unwrap_or_else(|| self.current_preset())
+    }
+
+    // board cells the shape in hand would cover if stamped under the mouse, which
+    // is hidden while dragging
+    pub fn ghost(&self) -> Vec<Position> {
+        match &self.hover {
+            Some(pos) if !self.painting() && !self.selecting => {
+                let board = &self.game_board;
+                let mut shape = self.current_shape();
+                shape.offset = Some(pos.clone());
+                shape.get_cells(&board.topology, board.width, board.height)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // saves the whole universe on unbounded engines, not just the window
+    pub fn save_board(&mut self) {
+        let Some(pattern) = self
+            .whole_board()
+            .map(|board| Pattern::new(board.to_shape(), Some(board.rule)))
+        else {
+            self.message = Some(String::from("Save failed: pattern too large"));
+            return;
+        };
+        self.message = Some(match pattern::save(&self.save_path, &pattern) {
+            Ok(()) => format!("Saved {}", self.save_path.display()),
+            Err(e) => format!("Save failed: {}", e),
+        });
+    }
 }
 
 #[cfg(test)]
@@ -283,12 +1605,24 @@ mod test {
 
     fn input_shape() -> Board {
         let shape = vec![(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)];
-        Board::new(6, 6, Some(shape), 0.0)
+        Board::new(6, 6, Some(shape), 0.0, Rule::default(), Topology::default())
     }
 
     fn expected_shape() -> Board {
         let shape = vec![(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)];
-        Board::new(6, 6, Some(shape), 0.0)
+        Board::new(6, 6, Some(shape), 0.0, Rule::default(), Topology::default())
+    }
+
+    fn new_game(board: Board, kind: EngineKind) -> GolState {
+        let engine = Engine::new(kind, &board).unwrap();
+        GolState::new(board, engine, Rect::default(), PathBuf::new())
+    }
+
+    // a game on an unbounded engine holding shape, which may reach past the board
+    fn seeded_game(board: Board, kind: EngineKind, shape: Shape) -> GolState {
+        let mut engine = Engine::new(kind, &board).unwrap();
+        engine.seed(&shape);
+        GolState::new(board, engine, Rect::default(), PathBuf::new())
     }
 
     #[test]
@@ -298,4 +1632,486 @@ mod test {
         let expected = expected_shape();
         assert_eq!(input.cells, expected.cells);
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_tick_matches_serial() {
+        let rules: [Rule; 2] = [Rule::default(), "345/2/4".parse().unwrap()];
+        for (width, height) in [(64, 32), (97, 61), (5, 3)] {
+            for rule in rules {
+                let mut board = Board::new(width, height, None, 0.0, rule, Topology::default());
+                board.randomize();
+                for _ in 0..10 {
+                    let mut serial = board.cells.clone();
+                    board.next_band(0, &mut serial);
+                    board.tick();
+                    assert_eq!(board.cells, serial);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_keeps_edits_while_panning() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let mut game = new_game(board, EngineKind::Sparse);
+        game.game_board
+            .add_shape((5, 5).into(), Shape::new(Shape::GLIDER.to_vec(), None));
+        game.pan(0, 4);
+        assert_eq!(game.game_board.cells[5][3], Cell::Alive);
+        assert_eq!(game.game_board.cells[5][7], Cell::Dead);
+        game.pan(0, -4);
+        game.advance(4);
+        game.center_view();
+        assert_eq!(game.game_board.to_shape().pattern.len(), 5);
+        assert_eq!(game.game_board.cells[3][4], Cell::Alive);
+    }
+
+    #[test]
+    fn test_seed_wider_than_window() {
+        let board = Board::new(16, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let row = (0..40).map(|column| (0, column)).collect();
+        let mut game = seeded_game(
+            board,
+            EngineKind::Sparse,
+            Shape::new(row, Some((2, 4).into())),
+        );
+        assert_eq!(game.stats().population, 40);
+        assert_eq!(game.game_board.population(), 12);
+        assert_eq!(game.stats().bounds, Some((4, 2, 43, 2)));
+        game.advance(1);
+        assert_eq!(game.stats().population, 38 * 3);
+    }
+
+    #[test]
+    fn test_bitpacked_keeps_edits() {
+        let mut board = Board::new(70, 20, None, 0.0, Rule::default(), Topology::default());
+        board.randomize();
+        let mut games =
+            [EngineKind::Dense, EngineKind::BitPacked].map(|kind| new_game(board.clone(), kind));
+        for game in &mut games {
+            game.advance(3);
+            game.edit(|board| {
+                board.add_shape((5, 65).into(), Shape::new(Shape::GLIDER.to_vec(), None))
+            });
+            game.advance(2);
+            game.undo();
+            game.advance(4);
+        }
+        let [dense, packed] = &games;
+        assert_eq!(dense.generation, packed.generation);
+        assert_eq!(dense.game_board.cells, packed.game_board.cells);
+    }
+
+    #[test]
+    fn test_save_whole_universe() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let path = std::env::temp_dir().join(format!("sp_gol_save_{}.cells", std::process::id()));
+        let mut game = new_game(board, EngineKind::Sparse);
+        game.save_path = path.clone();
+        game.edit(|board| board.add_shape((0, 0).into(), Shape::new(Shape::ACORN.to_vec(), None)));
+        game.pan(0, 2);
+        game.save_board();
+        let saved = pattern::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved.unwrap().shape.pattern.len(), Shape::ACORN.len());
+    }
+
+    #[test]
+    fn test_rewind() {
+        let board = input_shape();
+        let mut game = new_game(board, EngineKind::Dense);
+        game.advance(1);
+        let stats = game.stats();
+        assert_eq!((stats.population, stats.births, stats.deaths), (5, 2, 2));
+        assert_eq!(stats.bounds, Some((1, 2, 3, 4)));
+        assert_eq!(stats.step, 1);
+        game.advance(1);
+        game.step_back();
+        assert_eq!(game.generation, 1);
+        assert_eq!(game.game_board.cells, expected_shape().cells);
+        game.step_back();
+        assert_eq!(game.game_board.cells, input_shape().cells);
+        game.step_forward();
+        game.step_forward();
+        assert_eq!((game.generation, game.timeline.len()), (2, 3));
+
+        // an edit in the past replaces the generations that followed it
+        game.show_frame(0);
+        game.edit(|board| board.flip_cell((0, 0).into()));
+        assert_eq!(game.timeline.len(), 1);
+        game.undo();
+        game.undo();
+        assert_eq!(game.generation, 2);
+
+        // births and deaths of a larger step cover all of its generations
+        game.advance(4);
+        assert_eq!(game.stats().step, 4);
+    }
+
+    #[test]
+    fn test_rewind_unbounded() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let mut game = new_game(board, EngineKind::Sparse);
+        let glider = Shape::new(Shape::GLIDER.to_vec(), None);
+        game.edit(|board| board.add_shape((2, 2).into(), glider));
+        (0..20).for_each(|_| game.advance(1));
+        game.step_back();
+        game.show_frame(0);
+        assert_eq!(game.generation, 20);
+        assert!(game.message.is_some());
+        assert_eq!(game.stats().population, 5);
+    }
+
+    #[test]
+    fn test_undo_unbounded() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        // a blinker across the right edge of the window
+        let mut game = seeded_game(
+            board,
+            EngineKind::Sparse,
+            Shape::new(vec![(4, 6), (4, 7), (4, 8)], None),
+        );
+        game.edit(|board| board.flip_cell((0, 0).into()));
+        game.advance(1);
+        game.undo();
+        assert_eq!((game.generation, game.stats().population), (1, 3));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let mut game = new_game(board, EngineKind::Sparse);
+        let glider = Shape::new(Shape::GLIDER.to_vec(), None);
+        game.edit(|board| board.add_shape((2, 2).into(), glider));
+        (0..3).for_each(|_| game.advance(1));
+        assert_eq!(game.stats().cycle, None);
+        game.advance(1);
+        let cycle = game.stats().cycle.unwrap();
+        assert_eq!((cycle.generation, cycle.period), (0, 4));
+        assert_eq!(cycle.displacement, (1, 1));
+
+        // an edit made after the cycle was noticed ends it too
+        (0..4).for_each(|_| game.advance(1));
+        game.edit(|board| {
+            board.clear();
+            board.add_shape((2, 2).into(), Shape::new(Shape::R_PENTOMINO.to_vec(), None));
+        });
+        assert_eq!(game.stats().cycle, None);
+        game.advance(1);
+        assert_eq!(game.stats().cycle, None);
+
+        // an edit replaces the generations the cycle was found in
+        game.edit(Board::clear);
+        assert_eq!(game.stats().cycle, None);
+        game.advance(1);
+        let cycle = game.stats().cycle.unwrap();
+        assert_eq!((cycle.generation, cycle.period), (9, 1));
+    }
+
+    #[test]
+    fn test_headless() {
+        let blinker = vec![(2, 1), (2, 2), (2, 3)];
+        let board = Board::new(
+            5,
+            5,
+            Some(blinker),
+            0.0,
+            Rule::default(),
+            Topology::default(),
+        );
+        let engine = Engine::new(EngineKind::Dense, &board).unwrap();
+        let mut game = GolState::headless(board, engine);
+        game.advance(1);
+        game.advance(1);
+        assert_eq!(game.cycle().map(|cycle| cycle.period), Some(2));
+        assert_eq!(game.stats().births, 2);
+        // nothing is kept for the UI along the way
+        assert_eq!(game.timeline.len(), 1);
+        assert_eq!(game.populations.window(ChartWindow::All).len(), 1);
+        game.undo();
+        assert_eq!(game.generation, 2);
+    }
+
+    #[test]
+    fn test_every_generation_kept() {
+        let pentomino = Shape::R_PENTOMINO.iter().map(|(x, y)| (x + 8, y + 8));
+        let board = Board::new(
+            20,
+            20,
+            Some(pentomino.collect()),
+            0.0,
+            Rule::default(),
+            Topology::default(),
+        );
+        for kind in [EngineKind::Dense, EngineKind::BitPacked] {
+            let mut game = new_game(board.clone(), kind);
+            game.advance(5);
+            // the generations stepped over in one go can still be reached
+            assert_eq!(game.timeline.len(), 6);
+            assert_eq!(game.populations.window(ChartWindow::All).len(), 6);
+            let mut expected = board.clone();
+            for generation in 0..=5 {
+                assert_eq!(
+                    game.timeline.frame(generation),
+                    Some(expected.cells.clone())
+                );
+                let sample = (generation as u64, expected.population());
+                assert_eq!(
+                    game.populations.window(ChartWindow::All)[generation],
+                    sample
+                );
+                expected.tick();
+            }
+        }
+    }
+
+    #[test]
+    fn test_far_apart_pattern() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::Unbounded);
+        let mut game = seeded_game(
+            board,
+            EngineKind::HashLife,
+            Shape::new(Shape::ACORN.to_vec(), None),
+        );
+        game.advance(1 << 40);
+        assert_eq!(game.generation, 1 << 40);
+        assert_eq!(game.cycle(), None);
+        assert!(game.whole_board().is_none());
+        assert_eq!(game.take_census(), None);
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let board = Board::new(6, 6, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.term_rect = Rect::new(0, 0, 80, 60);
+        game.toggle_cursor();
+        assert_eq!(game.cursor, Some((3, 3).into()));
+        game.move_cursor(-10, 1);
+        assert_eq!(game.stats().cursor, Some((4, 0)));
+        game.toggle_at_cursor();
+        assert_eq!(game.game_board.cells[0][4], Cell::Alive);
+        game.move_cursor(1, -4);
+        game.cycle_presets();
+        game.stamp_at_cursor();
+        assert_eq!(game.game_board.cells[2][2], Cell::Alive);
+        assert_eq!(game.stats().population, 1 + Shape::GLIDER.len() as u64);
+        game.toggle_cursor();
+        assert_eq!(game.stats().cursor, None);
+    }
+
+    #[test]
+    fn test_paint_strokes() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.begin_stroke((0, 0).into(), false);
+        game.continue_stroke((3, 6).into());
+        game.end_stroke();
+        let painted = [(0, 0), (1, 1), (1, 2), (2, 3), (2, 4), (3, 5), (3, 6)];
+        assert_eq!(game.stats().population, painted.len() as u64);
+        assert!(painted
+            .iter()
+            .all(|&(row, column)| game.game_board.cells[row][column] == Cell::Alive));
+
+        // starting on a living cell, the toggle brush erases for the whole stroke
+        game.begin_stroke((1, 1).into(), false);
+        game.continue_stroke((7, 1).into());
+        game.continue_stroke((2, 4).into());
+        assert_eq!(game.stats().population, 5);
+        game.undo();
+        assert_eq!(game.stats().population, painted.len() as u64);
+        game.undo();
+        assert_eq!(game.stats().population, 0);
+
+        game.brush = Brush::Draw;
+        game.begin_stroke((5, 0).into(), false);
+        game.advance(1);
+        game.continue_stroke((5, 2).into());
+        game.end_stroke();
+        assert_eq!(game.stats().population, 3);
+
+        // each character of the quadrant mode paints a 2x2 block
+        game.edit(Board::clear);
+        game.cycle_render_mode();
+        game.cycle_render_mode();
+        game.begin_stroke((0, 0).into(), false);
+        game.continue_stroke((0, 4).into());
+        game.end_stroke();
+        assert_eq!(game.stats().population, 12);
+        assert_eq!(game.game_board.bounds(), Some((0, 0, 5, 1)));
+        game.begin_stroke((0, 2).into(), true);
+        game.end_stroke();
+        assert_eq!(game.stats().population, 8);
+    }
+
+    #[test]
+    fn test_shape_transforms() {
+        let cells = |shape: &Shape| {
+            let mut cells: Vec<(usize, usize)> =
+                shape.pattern.iter().cloned().map(Into::into).collect();
+            cells.sort_unstable();
+            cells
+        };
+        let acorn = Shape::new(Shape::ACORN.to_vec(), None);
+        let mut shape = acorn.clone();
+        shape.transform(Transform::Rotate);
+        assert_eq!(shape.size(), (7, 3));
+        shape.transform(Transform::FlipHorizontal);
+        let mut transposed = acorn.clone();
+        transposed.transform(Transform::Transpose);
+        assert_eq!(cells(&shape), cells(&transposed));
+        (0..3).for_each(|_| shape.transform(Transform::Rotate));
+        shape.transform(Transform::FlipVertical);
+        assert_eq!(cells(&shape), cells(&acorn));
+    }
+
+    #[test]
+    fn test_selection_clipboard() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.term_rect = Rect::new(0, 0, 80, 60);
+        let glider = Shape::new(Shape::GLIDER.to_vec(), None);
+        game.edit(|board| board.add_shape((1, 1).into(), glider));
+        game.begin_selection((3, 3).into());
+        game.extend_selection((0, 1).into());
+        game.end_selection();
+        game.extend_selection((7, 7).into());
+        assert_eq!(game.stats().selection, Some((3, 4)));
+
+        // the clipboard is trimmed to the living cells
+        game.cut_selection();
+        assert_eq!(game.stats().population, 0);
+        assert_eq!(game.stats().clipboard, Some((3, 3)));
+        game.transform_shape(Transform::Rotate);
+        game.hover = Some((4, 4).into());
+        game.paste();
+        let turned = [(4, 5), (5, 4), (6, 4), (6, 5), (6, 6)];
+        assert!(turned
+            .iter()
+            .all(|&(row, column)| game.game_board.cells[row][column] == Cell::Alive));
+        assert_eq!(game.stats().population, 5);
+
+        // marking from the cursor, then filling
+        game.toggle_selection();
+        assert_eq!(game.selection, None);
+        game.cursor = Some((0, 0).into());
+        game.toggle_selection();
+        game.move_cursor(1, 7);
+        game.density = 1.0;
+        game.fill_selection();
+        assert_eq!(game.stats().population, 5 + 16);
+        game.undo();
+        assert_eq!(game.stats().population, 5);
+    }
+
+    #[test]
+    fn test_preset_transforms() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.term_rect = Rect::new(0, 0, 80, 60);
+        game.cycle_presets();
+        // a glider mirrored left to right heads south-west
+        game.transform_shape(Transform::FlipHorizontal);
+        game.cursor = Some((2, 4).into());
+        game.stamp_at_cursor();
+        game.advance(4);
+        let glider = [(3, 3), (4, 3), (4, 5), (5, 3), (5, 4)];
+        assert!(glider
+            .iter()
+            .all(|&(row, column)| game.game_board.cells[row][column] == Cell::Alive));
+
+        // the preset keeps its orientation while the clipboard is in hand
+        game.clipboard = Some(Shape::new(vec![(0, 0)], None));
+        game.transform_shape(Transform::Rotate);
+        game.cycle_presets();
+        assert!(game.clipboard.is_none());
+        let mirrored = [(0, 0), (1, 0), (1, 2), (2, 0), (2, 1)];
+        let mut cells: Vec<(usize, usize)> = game
+            .current_shape()
+            .pattern
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        cells.sort_unstable();
+        assert_eq!(cells, mirrored);
+    }
+
+    #[test]
+    fn test_ghost_wraps() {
+        let board = Board::new(8, 8, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.cycle_presets();
+        assert!(game.ghost().is_empty());
+        game.hover = Some((6, 7).into());
+        let mut ghost: Vec<(usize, usize)> = game.ghost().into_iter().map(Into::into).collect();
+        ghost.sort_unstable();
+        assert_eq!(ghost, [(0, 0), (0, 1), (6, 1), (7, 1), (7, 7)]);
+
+        // out of the way while painting
+        game.begin_stroke((0, 0).into(), false);
+        assert!(game.ghost().is_empty());
+    }
+
+    #[test]
+    fn test_hover_follows_view() {
+        let board = Board::new(16, 16, None, 0.0, Rule::default(), Topology::default());
+        let mut game = new_game(board, EngineKind::Dense);
+        game.term_rect = Rect::new(0, 0, 80, 60);
+        let area = GolUi::new(game.term_rect, &game).game_area;
+        game.move_mouse(area.y + 1, area.x + 2);
+        let hover = game.hover.clone();
+        assert!(hover.is_some());
+        // zoomed out, the board shrinks away from under the mouse
+        game.zoom(false);
+        assert_eq!(game.hover, None);
+        game.zoom(true);
+        assert_eq!(game.hover, hover);
+    }
+
+    #[test]
+    fn test_run_at_speed() {
+        let board = input_shape();
+        let mut game = new_game(board, EngineKind::Dense);
+        game.speed = 10.0;
+        let far = Instant::now() + Duration::from_secs(60);
+        game.run(Duration::from_secs(1), far);
+        assert_eq!(game.generation, 0);
+        game.toggle_playpause();
+        game.run(Duration::from_millis(250), far);
+        assert_eq!(game.generation, 2);
+        game.run(Duration::from_millis(250), far);
+        assert_eq!(game.generation, 5);
+        game.change_speed(true);
+        assert_eq!(game.speed, 15.0);
+        // out of time, only the first step is taken
+        game.run(Duration::from_secs(60), Instant::now());
+        assert_eq!(game.generation, 6);
+        game.run(
+            Duration::from_secs(1),
+            Instant::now() + Duration::from_secs(60),
+        );
+        assert_eq!(game.generation, 21);
+    }
+
+    #[test]
+    fn test_tick_generations() {
+        let brians_brain: Rule = "/2/3".parse().unwrap();
+        let mut board = Board::new(
+            6,
+            6,
+            Some(vec![(2, 2), (2, 3)]),
+            0.0,
+            brians_brain,
+            Topology::default(),
+        );
+        board.tick();
+        assert_eq!(board.cells[2][2], Cell::Dying(1));
+        assert_eq!(board.cells[2][3], Cell::Dying(1));
+        assert_eq!(board.cells[1][2], Cell::Alive);
+        assert_eq!(board.cells[3][3], Cell::Alive);
+        board.tick();
+        assert_eq!(board.cells[2][2], Cell::Dead);
+        assert_eq!(board.cells[1][2], Cell::Dying(1));
+    }
 }
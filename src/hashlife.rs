@@ -0,0 +1,486 @@
+use crate::rule::Rule;
+use std::collections::HashMap;
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+// node tables are rebuilt from the live tree once they grow past this many nodes
+const GC_THRESHOLD: usize = 1 << 22;
+// the root never grows past 2^MAX_LEVEL cells across, which keeps every coordinate
+// and node size within an i64
+const MAX_LEVEL: u8 = 62;
+
+#[derive(Clone, Copy)]
+struct Node {
+    level: u8,
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    population: u64,
+}
+
+// Quadtree universe on the unbounded plane. Identical subtrees are shared and the
+// result of advancing each node is memoized, so periodic or sparse patterns can be
+// advanced by huge numbers of generations in logarithmic time.
+pub struct Universe {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    empty: Vec<NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    root: NodeId,
+    pub generation: u64,
+}
+
+impl Universe {
+    pub fn new(rule: Rule) -> Self {
+        let leaf = |population| Node {
+            level: 0,
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            population,
+        };
+        let mut universe = Universe {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            empty: vec![DEAD],
+            results: HashMap::new(),
+            root: DEAD,
+            generation: 0,
+        };
+        universe.root = universe.empty(3);
+        universe
+    }
+
+    // empty space must stay empty and cells need exactly two states
+    pub fn supports(rule: &Rule) -> bool {
+        rule.states == 2 && !rule.birth[0]
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(&id) = self.index.get(&[nw, ne, sw, se]) {
+            return id;
+        }
+        let population = [nw, ne, sw, se]
+            .iter()
+            .map(|&child| self.node(child).population)
+            .sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.node(nw).level + 1,
+            nw,
+            ne,
+            sw,
+            se,
+            population,
+        });
+        self.index.insert([nw, ne, sw, se], id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = self.empty[self.empty.len() - 1];
+            let id = self.join(below, below, below, below);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    fn level(&self) -> u8 {
+        self.node(self.root).level
+    }
+
+    fn half_size(&self) -> i64 {
+        size(self.level() - 1)
+    }
+
+    // double the root size while keeping its contents centered on the origin
+    fn expand(&mut self) -> Result<(), String> {
+        if self.level() >= MAX_LEVEL {
+            return Err(format!(
+                "the pattern has outgrown the 2^{} cells hashlife can address",
+                MAX_LEVEL
+            ));
+        }
+        let root = self.node(self.root);
+        let border = self.empty(root.level - 1);
+        let nw = self.join(border, border, border, root.nw);
+        let ne = self.join(border, border, root.ne, border);
+        let sw = self.join(border, root.sw, border, border);
+        let se = self.join(root.se, border, border, border);
+        self.root = self.join(nw, ne, sw, se);
+        Ok(())
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let half = self.half_size();
+        (-half..half).contains(&x) && (-half..half).contains(&y)
+    }
+
+    // cells too far out for the largest root are left alone
+    pub fn set_cell(&mut self, x: i64, y: i64, alive: bool) {
+        while !self.contains(x, y) {
+            if self.expand().is_err() {
+                return;
+            }
+        }
+        let half = self.half_size();
+        self.root = self.set_in(self.root, x + half, y + half, alive);
+    }
+
+    fn set_in(&mut self, id: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let node = self.node(id);
+        if node.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = size(node.level - 1);
+        let (mut nw, mut ne, mut sw, mut se) = (node.nw, node.ne, node.sw, node.se);
+        match (x < half, y < half) {
+            (true, true) => nw = self.set_in(nw, x, y, alive),
+            (false, true) => ne = self.set_in(ne, x - half, y, alive),
+            (true, false) => sw = self.set_in(sw, x, y - half, alive),
+            (false, false) => se = self.set_in(se, x - half, y - half, alive),
+        }
+        self.join(nw, ne, sw, se)
+    }
+
+    fn get_in(&self, id: NodeId, x: i64, y: i64) -> bool {
+        let node = self.node(id);
+        if node.population == 0 {
+            return false;
+        }
+        if node.level == 0 {
+            return true;
+        }
+        let half = size(node.level - 1);
+        match (x < half, y < half) {
+            (true, true) => self.get_in(node.nw, x, y),
+            (false, true) => self.get_in(node.ne, x - half, y),
+            (true, false) => self.get_in(node.sw, x, y - half),
+            (false, false) => self.get_in(node.se, x - half, y - half),
+        }
+    }
+
+    // living cells in the rectangle starting at (left, top), indexed [row][column]
+    pub fn window(&self, left: i64, top: i64, width: usize, height: usize) -> Vec<Vec<bool>> {
+        let mut cells = vec![vec![false; width]; height];
+        let half = self.half_size();
+        self.fill_window(self.root, (-half, -half), (left, top), &mut cells);
+        cells
+    }
+
+    fn fill_window(
+        &self,
+        id: NodeId,
+        (x, y): (i64, i64),
+        (left, top): (i64, i64),
+        cells: &mut Vec<Vec<bool>>,
+    ) {
+        let node = self.node(id);
+        let size = size(node.level);
+        let (width, height) = (cells.first().map_or(0, |row| row.len()), cells.len());
+        if node.population == 0
+            || x + size <= left
+            || y + size <= top
+            || x >= left + width as i64
+            || y >= top + height as i64
+        {
+            return;
+        }
+        if node.level == 0 {
+            cells[(y - top) as usize][(x - left) as usize] = true;
+            return;
+        }
+        let half = size / 2;
+        let origin = (left, top);
+        self.fill_window(node.nw, (x, y), origin, cells);
+        self.fill_window(node.ne, (x + half, y), origin, cells);
+        self.fill_window(node.sw, (x, y + half), origin, cells);
+        self.fill_window(node.se, (x + half, y + half), origin, cells);
+    }
+
+    pub fn population(&self) -> u64 {
+        self.node(self.root).population
+    }
+
+    // (left, top, right, bottom) of the living cells
+    pub fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let half = self.half_size();
+        let mut bounds = None;
+        self.extend_bounds(self.root, (-half, -half), &mut bounds);
+        bounds
+    }
+
+    fn extend_bounds(
+        &self,
+        id: NodeId,
+        (x, y): (i64, i64),
+        bounds: &mut Option<(i64, i64, i64, i64)>,
+    ) {
+        let node = self.node(id);
+        let size = size(node.level);
+        // nodes lying entirely within the bounds found so far can't widen them
+        let covered = |&(left, top, right, bottom): &(i64, i64, i64, i64)| {
+            x >= left && y >= top && x + size - 1 <= right && y + size - 1 <= bottom
+        };
+        if node.population == 0 || bounds.as_ref().is_some_and(covered) {
+            return;
+        }
+        if node.level == 0 {
+            *bounds = Some(match *bounds {
+                None => (x, y, x, y),
+                Some((left, top, right, bottom)) => {
+                    (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                }
+            });
+            return;
+        }
+        let half = size / 2;
+        self.extend_bounds(node.nw, (x, y), bounds);
+        self.extend_bounds(node.ne, (x + half, y), bounds);
+        self.extend_bounds(node.sw, (x, y + half), bounds);
+        self.extend_bounds(node.se, (x + half, y + half), bounds);
+    }
+
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let node = self.node(id);
+        let (nw, ne) = (self.node(node.nw), self.node(node.ne));
+        let (sw, se) = (self.node(node.sw), self.node(node.se));
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    fn centered_horizontal(&mut self, west: NodeId, east: NodeId) -> NodeId {
+        let (west, east) = (self.node(west), self.node(east));
+        self.join(west.ne, east.nw, west.se, east.sw)
+    }
+
+    fn centered_vertical(&mut self, north: NodeId, south: NodeId) -> NodeId {
+        let (north, south) = (self.node(north), self.node(south));
+        self.join(north.sw, north.se, south.nw, south.ne)
+    }
+
+    // advance a 4x4 node by one generation, giving its 2x2 center
+    fn step_base(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_in(id, x as i64, y as i64);
+            }
+        }
+        let mut next = [DEAD; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let neighbors = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && grid[ny][nx])
+                .count();
+            let alive = match grid[y][x] {
+                true => self.rule.survival[neighbors],
+                false => self.rule.birth[neighbors],
+            };
+            next[i] = if alive { ALIVE } else { DEAD };
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    // center half of a level k node advanced by 2^speed generations, speed <= k - 2
+    fn step(&mut self, id: NodeId, speed: u8) -> NodeId {
+        let node = self.node(id);
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, speed)) {
+            return result;
+        }
+        let result = if node.level == 2 {
+            self.step_base(id)
+        } else {
+            let full_speed = speed + 2 == node.level;
+            let overlapping = [
+                node.nw,
+                self.centered_horizontal(node.nw, node.ne),
+                node.ne,
+                self.centered_vertical(node.nw, node.sw),
+                self.center(id),
+                self.centered_vertical(node.ne, node.se),
+                node.sw,
+                self.centered_horizontal(node.sw, node.se),
+                node.se,
+            ];
+            // at full speed both halves of the jump are spent stepping, otherwise the
+            // first half only trims the overlapping nodes down to their centers
+            let first: Vec<NodeId> = overlapping
+                .into_iter()
+                .map(|sub| match full_speed {
+                    true => self.step(sub, speed - 1),
+                    false => self.center(sub),
+                })
+                .collect();
+            let second_speed = if full_speed { speed - 1 } else { speed };
+            let mut quadrants = [DEAD; 4];
+            for (i, [a, b, c, d]) in [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]]
+                .into_iter()
+                .enumerate()
+            {
+                let combined = self.join(first[a], first[b], first[c], first[d]);
+                quadrants[i] = self.step(combined, second_speed);
+            }
+            self.join(quadrants[0], quadrants[1], quadrants[2], quadrants[3])
+        };
+        self.results.insert((id, speed), result);
+        result
+    }
+
+    // whether every living cell sits in the middle quarter of the root
+    fn is_padded(&self) -> bool {
+        let root = self.node(self.root);
+        let inner: u64 = [
+            self.node(self.node(root.nw).se).se,
+            self.node(self.node(root.ne).sw).sw,
+            self.node(self.node(root.sw).ne).ne,
+            self.node(self.node(root.se).nw).nw,
+        ]
+        .iter()
+        .map(|&id| self.node(id).population)
+        .sum();
+        inner == root.population
+    }
+
+    // Stops early once the pattern needs a root larger than MAX_LEVEL, having
+    // advanced by whatever generation says
+    pub fn advance(&mut self, generations: u64) -> Result<(), String> {
+        let mut result = Ok(());
+        for speed in 0..u64::BITS as u8 {
+            if generations >> speed & 1 == 0 {
+                continue;
+            }
+            while self.level() < speed + 3 || !self.is_padded() {
+                result = self.expand();
+                if result.is_err() {
+                    break;
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+            self.root = self.step(self.root, speed);
+            self.generation += 1 << speed;
+        }
+        if self.nodes.len() > GC_THRESHOLD {
+            self.collect_garbage();
+        }
+        result
+    }
+
+    fn collect_garbage(&mut self) {
+        let mut fresh = Universe::new(self.rule);
+        let mut copied = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+        fresh.root = fresh.copy_from(self, self.root, &mut copied);
+        fresh.generation = self.generation;
+        *self = fresh;
+    }
+
+    fn copy_from(
+        &mut self,
+        other: &Universe,
+        id: NodeId,
+        copied: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if let Some(&new_id) = copied.get(&id) {
+            return new_id;
+        }
+        let node = other.node(id);
+        let nw = self.copy_from(other, node.nw, copied);
+        let ne = self.copy_from(other, node.ne, copied);
+        let sw = self.copy_from(other, node.sw, copied);
+        let se = self.copy_from(other, node.se, copied);
+        let new_id = self.join(nw, ne, sw, se);
+        copied.insert(id, new_id);
+        new_id
+    }
+}
+
+// cells across a node of level, which MAX_LEVEL keeps from overflowing
+fn size(level: u8) -> i64 {
+    debug_assert!(level <= MAX_LEVEL);
+    1 << level
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{Board, Cell, Shape},
+        topology::Topology,
+    };
+
+    #[test]
+    fn test_matches_dense_board() {
+        // far enough from the torus edges for the wrap-around never to matter
+        let mut board = Board::new(
+            256,
+            256,
+            Some(Shape::R_PENTOMINO.to_vec()),
+            50.0,
+            Rule::default(),
+            Topology::default(),
+        );
+        let mut universe = Universe::new(Rule::default());
+        for (row, cells) in board.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if *cell == Cell::Alive {
+                    universe.set_cell(column as i64, row as i64, true);
+                }
+            }
+        }
+        (0..100).for_each(|_| board.tick());
+        universe.advance(100).unwrap();
+        let window = universe.window(0, 0, 256, 256);
+        let dense: Vec<Vec<bool>> = board
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| *cell == Cell::Alive).collect())
+            .collect();
+        assert_eq!(universe.generation, 100);
+        assert_eq!(window, dense);
+    }
+
+    #[test]
+    fn test_exponential_jump() {
+        let mut universe = Universe::new(Rule::default());
+        for (row, column) in Shape::GLIDER {
+            universe.set_cell(column as i64, row as i64, true);
+        }
+        // a glider moves one cell diagonally every four generations
+        universe.advance(1 << 30).unwrap();
+        let shift = 1 << 28;
+        let mut glider = vec![vec![false; 5]; 5];
+        for (row, column) in Shape::GLIDER {
+            glider[row + 1][column + 1] = true;
+        }
+        assert_eq!(universe.window(shift - 1, shift - 1, 5, 5), glider);
+        assert!(!universe.window(-2, -2, 5, 5).concat().contains(&true));
+    }
+
+    #[test]
+    fn test_advance_stops_at_max_level() {
+        let mut universe = Universe::new(Rule::default());
+        for (row, column) in Shape::GLIDER {
+            universe.set_cell(column as i64, row as i64, true);
+        }
+        assert!(universe.advance(u64::MAX).is_err());
+        // steps of 2^60 generations and more would need a larger root
+        assert_eq!(universe.generation, (1 << 60) - 1);
+        assert_eq!(universe.population(), 5);
+        assert!(universe.bounds().is_some());
+    }
+}
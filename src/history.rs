@@ -0,0 +1,146 @@
+use crate::game::Cell;
+use std::collections::VecDeque;
+
+// One edit or step: the cells it changed as (row, column, before, after), and the
+// generation before and after it
+struct Change {
+    cells: Vec<(usize, usize, Cell, Cell)>,
+    generations: (u64, u64),
+}
+
+impl Change {
+    // what a change counts towards History::MAX_CELLS, so that changes without
+    // cells are still bounded
+    fn size(&self) -> usize {
+        self.cells.len().max(1)
+    }
+}
+
+// Undo and redo stacks of board changes. Only the cells that changed are kept, and
+// the oldest changes are forgotten once the stacks hold too many cells in total.
+// Steps that change no cells, as on a still life, are merged into one.
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
+    stored: usize,
+}
+
+impl History {
+    pub const MAX_CELLS: usize = 1 << 19;
+
+    // remember the change from before to after, dropping anything that was undone
+    pub fn record(&mut self, before: &[Vec<Cell>], after: &[Vec<Cell>], generations: (u64, u64)) {
+        let cells: Vec<_> = before
+            .iter()
+            .zip(after)
+            .enumerate()
+            .flat_map(|(row, (old, new))| {
+                old.iter()
+                    .zip(new)
+                    .enumerate()
+                    .filter(|(_, (old, new))| old != new)
+                    .map(move |(column, (old, new))| (row, column, *old, *new))
+            })
+            .collect();
+        if cells.is_empty() && generations.0 == generations.1 {
+            return;
+        }
+        self.stored -= self
+            .redo
+            .drain(..)
+            .map(|change| change.size())
+            .sum::<usize>();
+        if let Some(last) = self.undo.back_mut() {
+            if cells.is_empty() && last.cells.is_empty() && last.generations.1 == generations.0 {
+                last.generations.1 = generations.1;
+                return;
+            }
+        }
+        let change = Change { cells, generations };
+        self.stored += change.size();
+        self.undo.push_back(change);
+        while self.stored > History::MAX_CELLS {
+            match self.undo.pop_front() {
+                Some(oldest) => self.stored -= oldest.size(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn undo(&mut self, cells: &mut [Vec<Cell>], generation: &mut u64) -> bool {
+        match self.undo.pop_back() {
+            Some(change) => {
+                for &(row, column, before, _) in &change.cells {
+                    cells[row][column] = before;
+                }
+                *generation = change.generations.0;
+                self.redo.push(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, cells: &mut [Vec<Cell>], generation: &mut u64) -> bool {
+        match self.redo.pop() {
+            Some(change) => {
+                for &(row, column, _, after) in &change.cells {
+                    cells[row][column] = after;
+                }
+                *generation = change.generations.1;
+                self.undo.push_back(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = History::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = History::default();
+        let empty = vec![vec![Cell::Dead; 3]; 3];
+        let mut one = empty.clone();
+        one[1][1] = Cell::Alive;
+        let mut two = one.clone();
+        two[0][2] = Cell::Alive;
+        history.record(&empty, &one, (0, 0));
+        history.record(&one, &two, (0, 1));
+
+        let (mut cells, mut generation) = (two.clone(), 1);
+        assert!(history.undo(&mut cells, &mut generation));
+        assert_eq!((&cells, generation), (&one, 0));
+        assert!(history.undo(&mut cells, &mut generation));
+        assert_eq!(cells, empty);
+        assert!(!history.undo(&mut cells, &mut generation));
+        assert!(history.redo(&mut cells, &mut generation));
+        assert_eq!(cells, one);
+
+        // a new change replaces whatever could still be redone
+        history.record(&one, &empty, (0, 0));
+        assert!(!history.redo(&mut cells, &mut generation));
+        assert_eq!(history.stored, 2);
+    }
+
+    #[test]
+    fn test_steps_without_changes() {
+        let mut history = History::default();
+        let block = vec![vec![Cell::Alive; 2]; 2];
+        for generation in 0..1000 {
+            history.record(&block, &block, (generation, generation + 1));
+        }
+        assert_eq!((history.undo.len(), history.stored), (1, 1));
+        let (mut cells, mut generation) = (block.clone(), 1000);
+        assert!(history.undo(&mut cells, &mut generation));
+        assert_eq!(generation, 0);
+    }
+}
@@ -1,7 +1,23 @@
-use crate::game::GolState;
+use crate::{
+    game::{Board, GolState, Position, Transform},
+    ui::{GolUi, TimelineBar},
+};
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
+use tui::layout::Rect;
+
+// characters the view moves for each key press
+const PAN_STEP: i64 = 4;
+
+fn board_position(game: &GolState, row: u16, column: u16) -> Result<Position, ()> {
+    game.cell_under(row, column).ok_or(())
+}
+
+fn timeline_frame(game: &GolState, row: u16, column: u16) -> Option<usize> {
+    let area = GolUi::new(game.term_rect, game).timeline_area;
+    TimelineBar::frame_at(&game.timeline, area, row, column)
+}
 
 pub fn process_input(user_event: Event, game: &mut GolState) -> Result<(), ()> {
     match user_event {
@@ -19,14 +35,105 @@ pub fn process_input(user_event: Event, game: &mut GolState) -> Result<(), ()> {
             code: KeyCode::Char(' '),
             modifiers: KeyModifiers::NONE,
             ..
-        }) => Ok(game.toggle_playpause()),
+        }) => {
+            game.toggle_playpause();
+            Ok(())
+        }
+        // in cursor mode the arrows and hjkl move the cursor instead
+        Event::Key(KeyEvent {
+            code:
+                key @ (KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Char('h' | 'j' | 'k' | 'l')),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) if game.cursor.is_some() => {
+            match key {
+                KeyCode::Up | KeyCode::Char('k') => game.move_cursor(-1, 0),
+                KeyCode::Down | KeyCode::Char('j') => game.move_cursor(1, 0),
+                KeyCode::Left | KeyCode::Char('h') => game.move_cursor(0, -1),
+                _ => game.move_cursor(0, 1),
+            }
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Enter | KeyCode::Char('x'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) if game.cursor.is_some() => {
+            game.toggle_at_cursor();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('i'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) if game.cursor.is_some() => {
+            game.stamp_at_cursor();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('e'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.toggle_cursor();
+            Ok(())
+        }
         Event::Key(KeyEvent {
             code: KeyCode::Right,
             modifiers: KeyModifiers::NONE,
             ..
         }) => {
             if game.paused {
-                game.game_board.tick()
+                game.step_forward()
+            }
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Left,
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            if game.paused {
+                game.step_back()
+            }
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::SHIFT,
+        }) => {
+            if let Ok(position) = board_position(game, row, column) {
+                game.begin_selection(position);
+            }
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Drag(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) if game.selecting() => {
+            if let Ok(position) = board_position(game, row, column) {
+                game.extend_selection(position);
+            }
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) if !game.painting() && timeline_frame(game, row, column).is_some() => {
+            // scrubbing pauses so the chosen generation stays on screen
+            game.paused = true;
+            if let Some(index) = timeline_frame(game, row, column) {
+                game.show_frame(index);
             }
             Ok(())
         }
@@ -34,44 +141,265 @@ pub fn process_input(user_event: Event, game: &mut GolState) -> Result<(), ()> {
             code: KeyCode::Tab,
             modifiers: KeyModifiers::NONE,
             ..
-        })
-        | Event::Key(KeyEvent {
-            code: KeyCode::Char('s'),
+        }) => {
+            game.cycle_presets();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(direction @ ('w' | 'a' | 's' | 'd')),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            match direction {
+                'w' => game.pan(-PAN_STEP, 0),
+                's' => game.pan(PAN_STEP, 0),
+                'a' => game.pan(0, -PAN_STEP),
+                _ => game.pan(0, PAN_STEP),
+            }
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: direction @ (KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right),
+            modifiers: KeyModifiers::SHIFT,
+            ..
+        }) => {
+            match direction {
+                KeyCode::Up => game.pan(-PAN_STEP, 0),
+                KeyCode::Down => game.pan(PAN_STEP, 0),
+                KeyCode::Left => game.pan(0, -PAN_STEP),
+                _ => game.pan(0, PAN_STEP),
+            }
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: kind @ (MouseEventKind::ScrollUp | MouseEventKind::ScrollDown),
+            modifiers,
+            ..
+        }) => {
+            let step = match kind {
+                MouseEventKind::ScrollUp => -1,
+                _ => 1,
+            };
+            match modifiers {
+                KeyModifiers::CONTROL => game.zoom(step < 0),
+                KeyModifiers::SHIFT => game.pan(0, step * PAN_STEP),
+                _ => game.pan(step, 0),
+            }
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(bracket @ ('[' | ']')),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.zoom(bracket == ']');
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Home,
             modifiers: KeyModifiers::NONE,
             ..
-        }) => Ok(game.cycle_presets()),
+        }) => {
+            game.center_view();
+            Ok(())
+        }
         Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
+            kind: MouseEventKind::Down(button @ (MouseButton::Left | MouseButton::Right)),
             column,
             row,
             modifiers: KeyModifiers::NONE,
         }) => {
-            if let Ok(position) = game.game_board.in_bounds(row, column, game.term_rect) {
-                game.game_board.flip_cell(position);
+            if let Ok(position) = board_position(game, row, column) {
+                game.begin_stroke(position, button == MouseButton::Right);
+            }
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Drag(MouseButton::Left | MouseButton::Right),
+            column,
+            row,
+            ..
+        }) => {
+            if let Ok(position) = board_position(game, row, column) {
+                game.continue_stroke(position);
             }
             Ok(())
         }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Up(_),
+            ..
+        }) => {
+            game.end_stroke();
+            game.end_selection();
+            Ok(())
+        }
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            column,
+            row,
+            ..
+        }) => {
+            game.move_mouse(row, column);
+            Ok(())
+        }
         Event::Mouse(MouseEvent {
             kind: MouseEventKind::Down(MouseButton::Left),
             column,
             row,
             modifiers: KeyModifiers::ALT,
         }) => {
-            if let Ok(position) = game.game_board.in_bounds(row, column, game.term_rect) {
-                game.game_board.add_shape(position, game.current_preset());
+            if let Ok(position) = board_position(game, row, column) {
+                let shape = game.current_shape();
+                game.edit(|board| board.add_shape(position, shape));
             }
             Ok(())
         }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('v'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.toggle_selection();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(key @ ('c' | 'x' | 'v')),
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) => {
+            match key {
+                'c' => game.copy_selection(),
+                'x' => game.cut_selection(),
+                _ => game.paste(),
+            }
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('f'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.fill_selection();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(key @ ('t' | '|' | '_' | '/')),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            ..
+        }) => {
+            game.transform_shape(match key {
+                't' => Transform::Rotate,
+                '|' => Transform::FlipHorizontal,
+                '_' => Transform::FlipVertical,
+                _ => Transform::Transpose,
+            });
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('b'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.cycle_brush();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('m'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.cycle_render_mode();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('g'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.cycle_chart_window();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('?'),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            ..
+        }) => {
+            game.toggle_help();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('o'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.toggle_census();
+            Ok(())
+        }
         Event::Key(KeyEvent {
             code: KeyCode::Char('c'),
             modifiers: KeyModifiers::NONE,
             ..
-        }) => Ok(game.game_board.clear()),
+        }) => {
+            game.edit(Board::clear);
+            Ok(())
+        }
         Event::Key(KeyEvent {
             code: KeyCode::Char('r'),
             modifiers: KeyModifiers::NONE,
             ..
-        }) => Ok(game.game_board.randomize()),
+        }) => {
+            game.edit(Board::randomize);
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) => {
+            game.save_board();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(key @ ('+' | '=' | '-')),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            ..
+        }) => {
+            game.change_speed(key != '-');
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('n'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        }) => {
+            game.advance(game.step_size);
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('u'),
+            modifiers: KeyModifiers::NONE,
+            ..
+        })
+        | Event::Key(KeyEvent {
+            code: KeyCode::Char('z'),
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) => {
+            game.undo();
+            Ok(())
+        }
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('r' | 'y'),
+            modifiers: KeyModifiers::CONTROL,
+            ..
+        }) => {
+            game.redo();
+            Ok(())
+        }
+        Event::Resize(columns, rows) => {
+            game.resize(Rect::new(0, 0, columns, rows));
+            Ok(())
+        }
         _ => Ok(()),
     }
 }
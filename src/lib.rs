@@ -0,0 +1,19 @@
+// `Err(())` is used where the caller only needs to know that something didn't apply,
+// such as a click outside the board or a request to quit
+#![allow(clippy::result_unit_err)]
+
+pub mod bitboard;
+pub mod census;
+pub mod cycle;
+pub mod game;
+pub mod hashlife;
+pub mod history;
+pub mod input;
+pub mod pattern;
+pub mod rule;
+pub mod sparse;
+pub mod stats;
+pub mod timeline;
+pub mod topology;
+pub mod ui;
+pub mod viewport;
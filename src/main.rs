@@ -1,17 +1,33 @@
-mod game;
-mod input;
-mod ui;
-
 use clap::Parser;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use game::{Board, GolState, Shape};
-use std::{io, sync::mpsc::channel, thread};
+use sp_gol::{
+    game::{Board, Engine, EngineKind, GolState, Selection, Shape},
+    input,
+    pattern::{self, Format, Pattern},
+    rule::Rule,
+    topology::{GridSpec, Topology},
+    ui::{
+        BoardView, CensusPanel, ControlToggle, GolUi, HelpOverlay, PopulationChart, TimelineBar,
+        TooSmall,
+    },
+};
+use std::{
+    io,
+    path::PathBuf,
+    sync::mpsc::channel,
+    thread,
+    time::{Duration, Instant},
+};
 use tui::{backend::CrosstermBackend, Terminal};
-use ui::{ControlToggle, GolUi};
+
+// redraw at ~30 fps, whatever the simulation speed
+const FRAME_TIME: Duration = Duration::from_millis(33);
+// generations --until-stable gives up after when no --advance is given
+const UNTIL_STABLE_LIMIT: u64 = 100_000;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -19,10 +35,157 @@ struct Args {
     rows: u16,
     #[arg(short, long, default_value_t = 64)]
     columns: u16,
-    #[arg(short, long, default_value_t = String::from("thunderbird"))]
+    #[arg(
+        short,
+        long,
+        default_value_t = String::from("thunderbird"),
+        help = "Preset name or path to a pattern file (.rle, .cells, .lif)"
+    )]
     shape: String,
     #[arg(short, long, default_value_t = 50.0, help = "As percentage")]
     offset: f32,
+    #[arg(
+        long,
+        help = "B/S notation, e.g. B36/S23 or 23/36 [default: pattern's rule or B3/S23]"
+    )]
+    rule: Option<Rule>,
+    #[arg(
+        long,
+        help = "Golly grid, e.g. T64,32 torus, T64+5,32 shifted torus, P64,32 bounded plane, \
+                K64*,32 Klein bottle, C64,32 cross-surface or S64 sphere; overrides rows/columns"
+    )]
+    topology: Option<GridSpec>,
+    #[arg(
+        long,
+        default_value_t = EngineKind::Dense,
+        help = "dense, bitpacked, or hashlife/sparse to run on the unbounded plane with the board as a window"
+    )]
+    engine: EngineKind,
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Generations to advance before starting, or to run for with --headless"
+    )]
+    advance: u64,
+    #[arg(
+        long,
+        default_value_t = GolState::DEFAULT_SPEED,
+        value_parser = parse_speed,
+        help = "Generations per second while running"
+    )]
+    speed: f64,
+    #[arg(long, default_value_t = GolState::DEFAULT_STEP_SIZE, help = "Generations jumped by 'N'")]
+    step: u64,
+    #[arg(
+        long,
+        default_value_t = GolState::DEFAULT_DENSITY * 100.0,
+        help = "Percentage of cells 'F' brings to life in the selection"
+    )]
+    density: f64,
+    #[arg(
+        long,
+        default_value = "board.rle",
+        help = "File written by Ctrl-S, format chosen by extension"
+    )]
+    save: PathBuf,
+    #[arg(
+        long,
+        help = "Run --advance generations without the UI and print statistics instead"
+    )]
+    headless: bool,
+    #[arg(
+        long,
+        help = "Stop once the pattern repeats, giving up after --advance generations or 100000; \
+                implies --headless"
+    )]
+    until_stable: bool,
+    #[arg(
+        long,
+        help = "Write the final pattern to this file, or as RLE to stdout if '-'; implies --headless"
+    )]
+    output: Option<PathBuf>,
+    #[arg(long, help = "Also print the objects left; implies --headless")]
+    census: bool,
+}
+
+fn parse_speed(speed: &str) -> Result<f64, String> {
+    match speed.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("'{}' is not a positive number", speed)),
+    }
+}
+
+// Run the simulation and report on it without touching the terminal, so the output
+// can be piped
+fn run_headless(game_state: &mut GolState, args: &Args) -> io::Result<()> {
+    match args.until_stable {
+        true => {
+            // escaping gliders keep an unbounded pattern from ever repeating
+            let limit = match args.advance {
+                0 => UNTIL_STABLE_LIMIT,
+                generations => generations,
+            };
+            while game_state.generation < limit
+                && game_state.cycle().is_none()
+                && game_state.message.is_none()
+            {
+                game_state.advance(1);
+            }
+        }
+        false => game_state.advance(args.advance),
+    }
+    // the engine stopped short of the generations asked for
+    if let Some(message) = &game_state.message {
+        eprintln!("{}", message);
+    }
+
+    let stats = game_state.stats();
+    let mut report = format!(
+        "generation    {}\npopulation    {}\nbirths/deaths {}/{}",
+        stats.generation, stats.population, stats.births, stats.deaths
+    );
+    if stats.step > 1 {
+        report += &format!(" over the last {} generations", stats.step);
+    }
+    report += "\n";
+    if let Some((left, top, right, bottom)) = stats.bounds {
+        report += &format!("bounding box  {}x{}\n", right - left + 1, bottom - top + 1);
+    }
+    report += &match stats.cycle {
+        Some(cycle) => format!("{}\n", cycle),
+        None => String::from("not stabilized\n"),
+    };
+    let too_large = |what: &str| {
+        let message = format!("pattern too large for {}", what);
+        io::Error::new(io::ErrorKind::InvalidInput, message)
+    };
+    if args.census {
+        let census = game_state
+            .take_census()
+            .ok_or_else(|| too_large("a census"))?;
+        report += &format!("\n{}", census);
+    }
+
+    let Some(path) = &args.output else {
+        print!("{}", report);
+        return Ok(());
+    };
+    let board = game_state
+        .whole_board()
+        .ok_or_else(|| too_large("output"))?;
+    let pattern = Pattern::new(board.to_shape(), Some(board.rule));
+    match path.as_os_str() == "-" {
+        // the pattern takes stdout, so the report moves out of its way
+        true => {
+            print!("{}", Format::Rle.write(&pattern));
+            eprint!("{}", report);
+        }
+        false => {
+            pattern::save(path, &pattern)?;
+            print!("{}", report);
+        }
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), io::Error> {
@@ -38,7 +201,45 @@ fn main() -> Result<(), io::Error> {
         "bheptomino" => Some(Shape::B_HEPTOMINO.to_vec()),
         _ => None,
     };
-    let board = Board::new(args.columns, args.rows, init, args.offset);
+    let (init, pattern_rule) = match init {
+        Some(preset) => (Some(preset), None),
+        None => {
+            let pattern = pattern::load(args.shape.as_ref())?;
+            let cells = pattern.shape.pattern.into_iter().map(|pos| pos.into());
+            (Some(cells.collect()), pattern.rule)
+        }
+    };
+    let rule = args.rule.or(pattern_rule).unwrap_or_default();
+    let grid = args.topology.unwrap_or(GridSpec {
+        topology: match args.engine.is_unbounded() {
+            true => Topology::Unbounded,
+            false => Default::default(),
+        },
+        width: args.columns,
+        height: args.rows,
+    });
+    // on the unbounded plane the pattern goes straight into the universe, as the
+    // board would clip it to the window
+    let unbounded = grid.topology == Topology::Unbounded;
+    let board = Board::new(
+        grid.width,
+        grid.height,
+        init.clone().filter(|_| !unbounded),
+        args.offset,
+        rule,
+        grid.topology,
+    );
+    let mut engine = Engine::new(args.engine, &board)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    if let Some(cells) = init.filter(|_| unbounded) {
+        let offset = Board::offset(args.offset, grid.width, grid.height);
+        engine.seed(&Shape::new(cells, offset));
+    }
+
+    if args.headless || args.until_stable || args.output.is_some() || args.census {
+        let mut game_state = GolState::headless(board, engine);
+        return run_headless(&mut game_state, &args);
+    }
 
     // listen for user input
     let (tx, rx) = channel::<Event>();
@@ -58,36 +259,88 @@ fn main() -> Result<(), io::Error> {
 
     // initialize game state
     let term_rect = terminal.size().expect("Error getting terminal dimensions");
-    let mut game_state = GolState::new(board, term_rect);
+    let mut game_state = GolState::new(board, engine, term_rect, args.save);
+    // bring a pattern larger than the window into view
+    if game_state.stats().population > game_state.game_board.population() {
+        game_state.center_view();
+    }
+    game_state.speed = args.speed;
+    game_state.step_size = args.step;
+    game_state.density = args.density.clamp(0.0, 100.0) / 100.0;
+    game_state.advance(args.advance);
 
     // draw loop
-    loop {
-        if let Ok(user_event) = rx.try_recv() {
-            if input::process_input(user_event, &mut game_state).is_err() {
-                break;
+    let mut last_run = Instant::now();
+    'frames: loop {
+        terminal.draw(|frame| {
+            if !GolUi::fits(frame.size()) {
+                frame.render_widget(TooSmall, frame.size());
+                return;
             }
-        } else {
-            terminal.draw(|frame| {
-                let board = game_state.game_board.clone();
-                let layout = GolUi::new(frame.size(), &board);
-                frame.render_widget(layout.screen_border, frame.size());
-                frame.render_widget(layout.controls_border, layout.controls_row);
-                frame.render_widget(board, layout.game_area);
-                frame.render_widget(layout.controls_list, layout.controls_list_area);
-                frame.render_widget(game_state.current_preset(), layout.shape_display_area);
-                frame.render_widget(
-                    match game_state.paused {
-                        true => ControlToggle::Play,
-                        false => ControlToggle::Pause,
+            let layout = GolUi::new(frame.size(), &game_state);
+            frame.render_widget(layout.screen_border, frame.size());
+            frame.render_widget(layout.controls_border, layout.controls_row);
+            frame.render_widget(
+                BoardView {
+                    board: &game_state.game_board,
+                    viewport: game_state.viewport,
+                    cursor: game_state.cursor.clone(),
+                    selection: game_state.selection.as_ref().map(Selection::bounds),
+                    ghost: game_state.ghost(),
+                },
+                layout.game_area,
+            );
+            frame.render_widget(layout.controls_list, layout.controls_list_area);
+            frame.render_widget(game_state.current_shape(), layout.shape_display_area);
+            match game_state.show_census {
+                true => frame.render_widget(
+                    CensusPanel {
+                        census: game_state.census(),
+                        settled: game_state.stats().cycle.is_some(),
+                    },
+                    layout.chart_area,
+                ),
+                false => frame.render_widget(
+                    PopulationChart {
+                        samples: game_state.populations.window(game_state.chart_window),
+                        window: game_state.chart_window,
                     },
-                    layout.playpause_toggle_area,
-                );
-            })?;
-            if !game_state.paused {
-                game_state.game_board.tick();
+                    layout.chart_area,
+                ),
+            }
+            frame.render_widget(game_state.stats(), layout.stats_area);
+            frame.render_widget(
+                TimelineBar {
+                    timeline: &game_state.timeline,
+                    generation: game_state.generation,
+                },
+                layout.timeline_area,
+            );
+            frame.render_widget(
+                match game_state.paused {
+                    true => ControlToggle::Play,
+                    false => ControlToggle::Pause,
+                },
+                layout.playpause_toggle_area,
+            );
+            if game_state.show_help {
+                frame.render_widget(HelpOverlay, frame.size());
+            }
+        })?;
+
+        // handle every event that arrives until the next frame is due
+        let next_frame = Instant::now() + FRAME_TIME;
+        while let Ok(user_event) =
+            rx.recv_timeout(next_frame.saturating_duration_since(Instant::now()))
+        {
+            if input::process_input(user_event, &mut game_state).is_err() {
+                break 'frames;
             }
-            std::thread::sleep(std::time::Duration::from_millis(50)); // redraw @ ~15 fps
         }
+        // stepping gets at most half a frame, leaving the rest for input
+        let now = Instant::now();
+        game_state.run(now - last_run, now + FRAME_TIME / 2);
+        last_run = now;
     }
 
     // restore terminal on exit
@@ -0,0 +1,82 @@
+use super::{Pattern, MAX_EXTENT};
+use crate::game::Shape;
+
+pub const HEADER: &str = "#Life 1.06";
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::new(Shape::new(Vec::new(), None), None);
+    let mut cells = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(HEADER) {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            pattern.comments.push(comment.trim().to_string());
+            continue;
+        }
+        let coordinates: Vec<i64> = line
+            .split_whitespace()
+            .map(|n| n.parse::<i64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("invalid coordinates '{}'", line))?;
+        match coordinates[..] {
+            // coordinates are given as x y, i.e. column first
+            [x, y] => cells.push((y, x)),
+            _ => return Err(format!("expected two coordinates in '{}'", line)),
+        }
+    }
+
+    // as with RLE, cells spread over more than MAX_EXTENT rows or columns are refused
+    for axis in [|cell: &(i64, i64)| cell.0, |cell: &(i64, i64)| cell.1] {
+        let low = cells.iter().map(axis).min().unwrap_or(0);
+        let high = cells.iter().map(axis).max().unwrap_or(0);
+        if high.abs_diff(low) >= MAX_EXTENT as u64 {
+            return Err(format!(
+                "Life 1.06 pattern is larger than {0}x{0}",
+                MAX_EXTENT
+            ));
+        }
+    }
+
+    pattern.shape = Shape::normalized(cells);
+    Ok(pattern)
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut out = format!("{}\n", HEADER);
+    for pos in &pattern.shape.pattern {
+        out.push_str(&format!("{} {}\n", pos.column, pos.row));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_negative_coordinates() {
+        let pattern = parse("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+        let cells: Vec<(usize, usize)> = pattern
+            .shape
+            .pattern
+            .iter()
+            .map(|pos| (pos.row, pos.column))
+            .collect();
+        assert_eq!(cells, vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(write(&pattern), "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+    }
+
+    #[test]
+    fn test_too_large() {
+        let error = parse("-9223372036854775808 0\n9223372036854775807 0\n").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("Life 1.06 pattern is larger than 1048576x1048576")
+        );
+        assert!(parse("0 0\n0 1048576\n").is_err());
+        let pattern = parse("0 -5\n0 1048570\n").unwrap();
+        assert_eq!(pattern.shape.size(), (1048576, 1));
+    }
+}
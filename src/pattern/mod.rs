@@ -0,0 +1,127 @@
+mod life106;
+mod plaintext;
+mod rle;
+
+use crate::{game::Shape, rule::Rule};
+use std::{fs, io, path::Path};
+
+// patterns reaching further than this many rows or columns are refused rather than
+// expanded
+const MAX_EXTENT: usize = 1 << 20;
+
+#[derive(Clone)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    pub shape: Shape,
+}
+
+impl Pattern {
+    pub fn new(shape: Shape, rule: Option<Rule>) -> Self {
+        Pattern {
+            name: None,
+            author: None,
+            comments: Vec::new(),
+            rule,
+            shape,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Rle,
+    Plaintext,
+    Life106,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" => Some(Format::Plaintext),
+            "lif" | "life" => Some(Format::Life106),
+            _ => None,
+        }
+    }
+
+    // guess the format from the first meaningful line of the file
+    pub fn detect(text: &str) -> Option<Format> {
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            return match line {
+                _ if line.starts_with(life106::HEADER) => Some(Format::Life106),
+                _ if line.starts_with('!') => Some(Format::Plaintext),
+                _ if line.starts_with('#') => continue,
+                _ if line.starts_with('x') && line.contains('=') => Some(Format::Rle),
+                _ if line.chars().all(|c| matches!(c, '.' | 'O' | '*')) => Some(Format::Plaintext),
+                _ if line
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '-' || c == ' ') =>
+                {
+                    Some(Format::Life106)
+                }
+                _ => None,
+            };
+        }
+        None
+    }
+
+    pub fn parse(self, text: &str) -> Result<Pattern, String> {
+        match self {
+            Format::Rle => rle::parse(text),
+            Format::Plaintext => plaintext::parse(text),
+            Format::Life106 => life106::parse(text),
+        }
+    }
+
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::write(pattern),
+            Format::Plaintext => plaintext::write(pattern),
+            Format::Life106 => life106::write(pattern),
+        }
+    }
+}
+
+fn with_path(path: &Path, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), message),
+    )
+}
+
+pub fn load(path: &Path) -> io::Result<Pattern> {
+    let text = fs::read_to_string(path).map_err(|e| with_path(path, e))?;
+    let format = Format::detect(&text)
+        .or_else(|| Format::from_path(path))
+        .unwrap_or(Format::Rle);
+    format.parse(&text).map_err(|e| with_path(path, e))
+}
+
+pub fn save(path: &Path, pattern: &Pattern) -> io::Result<()> {
+    let format = Format::from_path(path).unwrap_or(Format::Rle);
+    fs::write(path, format.write(pattern))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = Format::detect;
+        assert_eq!(
+            detect("#N Glider\nx = 3, y = 3\nbo$2bo$3o!"),
+            Some(Format::Rle)
+        );
+        assert_eq!(
+            detect("!Name: Glider\n.O.\n..O\nOOO"),
+            Some(Format::Plaintext)
+        );
+        assert_eq!(detect(".O.\n..O\nOOO"), Some(Format::Plaintext));
+        assert_eq!(detect("#Life 1.06\n0 -1\n1 0"), Some(Format::Life106));
+        assert_eq!(detect("bo$2bo$3o!"), None);
+    }
+}
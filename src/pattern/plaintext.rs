@@ -0,0 +1,65 @@
+use super::Pattern;
+use crate::game::Shape;
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::new(Shape::new(Vec::new(), None), None);
+    let mut cells = Vec::new();
+    let mut row = 0;
+
+    for line in text.lines().map(str::trim_end) {
+        if let Some(comment) = line.strip_prefix('!') {
+            match comment.strip_prefix("Name:") {
+                Some(name) => pattern.name = Some(name.trim().to_string()),
+                None => pattern.comments.push(comment.trim().to_string()),
+            }
+            continue;
+        }
+        for (column, c) in line.chars().enumerate() {
+            match c {
+                '.' => (),
+                'O' | '*' => cells.push((row, column)),
+                _ => return Err(format!("unexpected character '{}' in plaintext data", c)),
+            }
+        }
+        row += 1;
+    }
+
+    pattern.shape = Shape::new(cells, None);
+    Ok(pattern)
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let cells = &pattern.shape.pattern;
+    let width = cells.iter().map(|pos| pos.column + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|pos| pos.row + 1).max().unwrap_or(0);
+    let mut grid = vec![vec!['.'; width]; height];
+    cells.iter().for_each(|pos| grid[pos.row][pos.column] = 'O');
+
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("!Name: {}\n", name));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("!{}\n", comment));
+    }
+    for row in grid {
+        out.extend(row);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "!Name: Glider\n!A small spaceship\n.O.\n..O\nOOO\n";
+        let pattern = parse(text).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.comments, vec!["A small spaceship"]);
+        assert_eq!(pattern.shape.pattern.len(), 5);
+        assert_eq!(write(&pattern), text);
+    }
+}
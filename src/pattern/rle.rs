@@ -0,0 +1,246 @@
+use super::{Pattern, MAX_EXTENT};
+use crate::{game::Shape, rule::Rule};
+
+const MAX_LINE_LENGTH: usize = 70;
+// a pattern within MAX_EXTENT can still be filled with far more cells than fit in
+// memory, so the living cells are capped too
+const MAX_CELLS: usize = 1 << 22;
+
+fn parse_header(line: &str, pattern: &mut Pattern) -> Result<(), String> {
+    for field in line.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("malformed header field '{}'", field.trim()))?;
+        match key.trim() {
+            "x" | "y" => {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid pattern size '{}'", value.trim()))?;
+            }
+            // Golly appends the grid topology to the rule, e.g. B3/S23:T64,32
+            "rule" => {
+                let rule = value.split(':').next().unwrap_or_default();
+                pattern.rule = Some(rule.trim().parse::<Rule>()?)
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::new(Shape::new(Vec::new(), None), None);
+    let mut cells = Vec::new();
+    let (mut row, mut column, mut count): (usize, usize, usize) = (0, 0, 0);
+    let mut header_seen = false;
+
+    'lines: for line in text.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let kind = chars.next();
+            let content = chars.as_str().trim().to_string();
+            match kind {
+                Some('N') => pattern.name = Some(content),
+                Some('O') => pattern.author = Some(content),
+                Some('C') | Some('c') => pattern.comments.push(content),
+                _ => (),
+            }
+            continue;
+        }
+        if !header_seen && line.starts_with('x') {
+            parse_header(line, &mut pattern)?;
+            header_seen = true;
+            continue;
+        }
+
+        for c in line.chars() {
+            let run = count.max(1);
+            match c {
+                '0'..='9' => {
+                    count = count
+                        .checked_mul(10)
+                        .and_then(|count| {
+                            count.checked_add(c.to_digit(10).unwrap_or_default() as usize)
+                        })
+                        .filter(|count| *count <= MAX_EXTENT)
+                        .ok_or_else(|| format!("run length in RLE data is over {}", MAX_EXTENT))?;
+                    continue;
+                }
+                'b' | '.' => column += run,
+                '$' => {
+                    row += run;
+                    column = 0;
+                }
+                '!' => break 'lines,
+                // any other state letter counts as a living cell
+                c if c.is_ascii_alphabetic() && column + run > MAX_EXTENT => {
+                    return Err(format!("RLE pattern is larger than {0}x{0}", MAX_EXTENT));
+                }
+                c if c.is_ascii_alphabetic() && cells.len() + run > MAX_CELLS => {
+                    return Err(format!(
+                        "RLE pattern has more than {} living cells",
+                        MAX_CELLS
+                    ));
+                }
+                c if c.is_ascii_alphabetic() => {
+                    cells.extend((column..column + run).map(|col| (row, col)));
+                    column += run;
+                }
+                c if c.is_whitespace() => (),
+                _ => return Err(format!("unexpected character '{}' in RLE data", c)),
+            }
+            if row > MAX_EXTENT || column > MAX_EXTENT {
+                return Err(format!("RLE pattern is larger than {0}x{0}", MAX_EXTENT));
+            }
+            count = 0;
+        }
+    }
+
+    pattern.shape = Shape::new(cells, None);
+    Ok(pattern)
+}
+
+fn run_token(length: usize, tag: char) -> String {
+    match length {
+        1 => tag.to_string(),
+        _ => format!("{}{}", length, tag),
+    }
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let cells = &pattern.shape.pattern;
+    let width = cells.iter().map(|pos| pos.column + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|pos| pos.row + 1).max().unwrap_or(0);
+    let mut grid = vec![vec![false; width]; height];
+    cells
+        .iter()
+        .for_each(|pos| grid[pos.row][pos.column] = true);
+
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        out.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("#C {}\n", comment));
+    }
+    out.push_str(&format!("x = {}, y = {}", width, height));
+    if let Some(rule) = &pattern.rule {
+        out.push_str(&format!(", rule = {}", rule));
+    }
+    out.push('\n');
+
+    let mut tokens = Vec::new();
+    let mut pending_rows = 0;
+    for (r, row) in grid.iter().enumerate() {
+        if r > 0 {
+            pending_rows += 1;
+        }
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for &alive in row {
+            match runs.last_mut() {
+                Some((state, length)) if *state == alive => *length += 1,
+                _ => runs.push((alive, 1)),
+            }
+        }
+        if let Some((false, _)) = runs.last() {
+            runs.pop();
+        }
+        if runs.is_empty() {
+            continue;
+        }
+        if pending_rows > 0 {
+            tokens.push(run_token(pending_rows, '$'));
+            pending_rows = 0;
+        }
+        for (alive, length) in runs {
+            tokens.push(run_token(length, if alive { 'o' } else { 'b' }));
+        }
+    }
+    tokens.push(String::from("!"));
+
+    let mut line = String::new();
+    for token in tokens {
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GLIDER_RLE: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+    fn sorted_cells(shape: &Shape) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = shape
+            .pattern
+            .iter()
+            .map(|pos| (pos.row, pos.column))
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn test_parse() {
+        let pattern = parse(GLIDER_RLE).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(pattern.comments.len(), 1);
+        assert_eq!(pattern.rule, Some(Rule::default()));
+        assert_eq!(
+            sorted_cells(&pattern.shape),
+            vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_huge_runs() {
+        assert!(parse("99999999999999999999999o!").is_err());
+        assert!(parse("1048576bo!").is_err());
+        assert!(parse("1048575b$1048576$o!").is_err());
+        assert_eq!(parse("1048575bo!").unwrap().shape.pattern.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_rejects_too_many_cells() {
+        let error = parse(&"1048575o$".repeat(5)).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("RLE pattern has more than 4194304 living cells")
+        );
+        assert_eq!(
+            parse(&"1048575o$".repeat(2)).unwrap().shape.pattern.len(),
+            2097150
+        );
+    }
+
+    #[test]
+    fn test_write_roundtrip() {
+        assert_eq!(write(&parse(GLIDER_RLE).unwrap()), GLIDER_RLE);
+
+        let gapped = Shape::new(vec![(0, 0), (0, 4), (3, 1), (3, 2)], None);
+        let text = write(&Pattern::new(gapped.clone(), None));
+        assert_eq!(text, "x = 5, y = 4\no3bo3$b2o!\n");
+        assert_eq!(
+            sorted_cells(&parse(&text).unwrap().shape),
+            sorted_cells(&gapped)
+        );
+    }
+}
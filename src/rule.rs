@@ -0,0 +1,144 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+    // number of cell states for Generations rules, 2 for plain Life-like rules
+    pub states: u8,
+}
+
+impl Rule {
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mut rule = Rule {
+            birth: [false; 9],
+            survival: [false; 9],
+            states: 2,
+        };
+        birth.iter().for_each(|&n| rule.birth[n as usize] = true);
+        survival
+            .iter()
+            .for_each(|&n| rule.survival[n as usize] = true);
+        rule
+    }
+
+    fn parse_counts(digits: &str, counts: &mut [bool; 9]) -> Result<(), String> {
+        for c in digits.chars() {
+            match c.to_digit(10) {
+                Some(n) if n <= 8 => counts[n as usize] = true,
+                _ => return Err(format!("'{}' is not a neighbor count (0-8)", c)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_states(part: &str) -> Result<u8, String> {
+        let digits = part.strip_prefix('C').unwrap_or(part);
+        match digits.parse::<u8>() {
+            Ok(states) if states >= 2 => Ok(states),
+            _ => Err(format!("'{}' is not a number of states (2-255)", part)),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::new(&[3], &[2, 3])
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let notation = s.trim().to_ascii_uppercase();
+        let mut rule = Rule::new(&[], &[]);
+        let mut parts: Vec<&str> = notation.split('/').collect();
+
+        // Generations rules carry the number of states as a third part
+        if parts.len() == 3 {
+            rule.states =
+                Rule::parse_states(parts[2]).map_err(|e| format!("invalid rule '{}': {}", s, e))?;
+            parts.truncate(2);
+        }
+        if parts.len() != 2 {
+            return Err(format!(
+                "invalid rule '{}': expected B<digits>/S<digits>[/C<states>] or <survival>/<birth>[/<states>]",
+                s
+            ));
+        }
+
+        if notation.contains(['B', 'S']) {
+            // "B3/S23" notation, parts may come in either order
+            for part in parts {
+                if let Some(digits) = part.strip_prefix('B') {
+                    Rule::parse_counts(digits, &mut rule.birth)
+                } else if let Some(digits) = part.strip_prefix('S') {
+                    Rule::parse_counts(digits, &mut rule.survival)
+                } else {
+                    Err(format!("'{}' should start with B or S", part))
+                }
+                .map_err(|e| format!("invalid rule '{}': {}", s, e))?;
+            }
+        } else {
+            // "23/3" notation, survival counts first
+            Rule::parse_counts(parts[0], &mut rule.survival)
+                .and_then(|_| Rule::parse_counts(parts[1], &mut rule.birth))
+                .map_err(|e| format!("invalid rule '{}': {}", s, e))?;
+        }
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_notations() {
+        let conway = Rule::default();
+        assert_eq!("B3/S23".parse::<Rule>(), Ok(conway));
+        assert_eq!("s23/b3".parse::<Rule>(), Ok(conway));
+        assert_eq!("23/3".parse::<Rule>(), Ok(conway));
+        assert_eq!("B2/S".parse::<Rule>(), Ok(Rule::new(&[2], &[])));
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("B3".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn test_parse_generations() {
+        let brians_brain = Rule {
+            states: 3,
+            ..Rule::new(&[2], &[])
+        };
+        assert_eq!("/2/3".parse::<Rule>(), Ok(brians_brain));
+        assert_eq!("B2/S/C3".parse::<Rule>(), Ok(brians_brain));
+        assert_eq!(brians_brain.to_string(), "B2/S/C3");
+        let star_wars: Rule = "345/2/4".parse().unwrap();
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        assert!("B2/S/C1".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let day_and_night: Rule = "34678/3678".parse().unwrap();
+        assert_eq!(day_and_night.to_string(), "B3678/S34678");
+        assert_eq!(day_and_night.to_string().parse(), Ok(day_and_night));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    game::{Board, Cell},
+    rule::Rule,
+};
+use std::collections::HashMap;
+
+// Unbounded universe storing only the cells that are not dead, keyed by (x, y).
+// Unlike HashLife it handles Generations rules, at the cost of visiting every
+// living cell each generation.
+pub struct Universe {
+    rule: Rule,
+    cells: HashMap<(i64, i64), Cell>,
+    pub generation: u64,
+}
+
+impl Universe {
+    pub fn new(rule: Rule) -> Self {
+        Universe {
+            rule,
+            cells: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    // empty space must stay empty
+    pub fn supports(rule: &Rule) -> bool {
+        !rule.birth[0]
+    }
+
+    pub fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        match cell {
+            Cell::Dead => self.cells.remove(&(x, y)),
+            _ => self.cells.insert((x, y), cell),
+        };
+    }
+
+    fn step(&mut self) {
+        let mut neighbors: HashMap<(i64, i64), usize> = HashMap::new();
+        for (&(x, y), _) in self.cells.iter().filter(|(_, cell)| **cell == Cell::Alive) {
+            for (dy, dx) in Board::NEIGHBORHOOD {
+                *neighbors.entry((x + dx, y + dy)).or_default() += 1;
+            }
+        }
+
+        let (rule, states) = (&self.rule, self.rule.states);
+        let mut next: HashMap<(i64, i64), Cell> = self
+            .cells
+            .iter()
+            .map(|(&pos, &cell)| {
+                let count = neighbors.get(&pos).copied().unwrap_or_default();
+                let next_cell = match cell {
+                    Cell::Alive if rule.survival[count] => Cell::Alive,
+                    Cell::Alive => Cell::decay(0, states),
+                    Cell::Dying(age) => Cell::decay(age, states),
+                    Cell::Dead => Cell::Dead,
+                };
+                (pos, next_cell)
+            })
+            .filter(|(_, cell)| *cell != Cell::Dead)
+            .collect();
+        for (pos, count) in neighbors {
+            if rule.birth[count] && !self.cells.contains_key(&pos) {
+                next.insert(pos, Cell::Alive);
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    pub fn advance(&mut self, generations: u64) {
+        (0..generations).for_each(|_| self.step());
+    }
+
+    // cells in the rectangle starting at (left, top), indexed [row][column]
+    pub fn window(&self, left: i64, top: i64, width: usize, height: usize) -> Vec<Vec<Cell>> {
+        let mut window = vec![vec![Cell::Dead; width]; height];
+        for (&(x, y), &cell) in &self.cells {
+            let (column, row) = (x - left, y - top);
+            if (0..width as i64).contains(&column) && (0..height as i64).contains(&row) {
+                window[row as usize][column as usize] = cell;
+            }
+        }
+        window
+    }
+
+    pub fn population(&self) -> u64 {
+        self.cells
+            .values()
+            .filter(|cell| **cell == Cell::Alive)
+            .count() as u64
+    }
+
+    // (left, top, right, bottom) of the cells that are not dead
+    pub fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        self.cells
+            .keys()
+            .fold(None, |bounds, &(x, y)| match bounds {
+                None => Some((x, y, x, y)),
+                Some((left, top, right, bottom)) => {
+                    Some((left.min(x), top.min(y), right.max(x), bottom.max(y)))
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::Shape;
+
+    #[test]
+    fn test_glider_leaves_origin() {
+        let mut universe = Universe::new(Rule::default());
+        for (row, column) in Shape::GLIDER {
+            universe.set_cell(column as i64, row as i64, Cell::Alive);
+        }
+        universe.advance(400);
+        assert_eq!(universe.bounds(), Some((100, 100, 102, 102)));
+        let window = universe.window(100, 100, 3, 3);
+        for (row, column) in Shape::GLIDER {
+            assert_eq!(window[row][column], Cell::Alive);
+        }
+    }
+}
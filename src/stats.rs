@@ -0,0 +1,147 @@
+use crate::{cycle::Cycle, game::Brush};
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+
+// Live numbers shown in the status panel
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub generation: u64,
+    // generations per second asked for
+    pub speed: f64,
+    pub population: u64,
+    // cells born and cells that stopped living in the last step, between its first
+    // and last generation when it was more than one
+    pub births: u64,
+    pub deaths: u64,
+    pub step: u64,
+    // (left, top, right, bottom) of the living pattern
+    pub bounds: Option<(i64, i64, i64, i64)>,
+    // generations per second achieved
+    pub rate: f64,
+    pub cycle: Option<Cycle>,
+    // (x, y) of the keyboard cursor, in the same coordinates as bounds
+    pub cursor: Option<(i64, i64)>,
+    pub brush: Brush,
+    // (width, height) of the selection and of what was last copied
+    pub selection: Option<(usize, usize)>,
+    pub clipboard: Option<(usize, usize)>,
+}
+
+// Generations per second, measured over the last RateMeter::WINDOW of wall time
+#[derive(Default)]
+pub struct RateMeter {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RateMeter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn record(&mut self, now: Instant, generations: u64) {
+        self.samples.push_back((now, generations));
+        while let Some((time, _)) = self.samples.front() {
+            match now.duration_since(*time) > RateMeter::WINDOW {
+                true => self.samples.pop_front(),
+                false => break,
+            };
+        }
+    }
+
+    pub fn rate(&self, now: Instant) -> f64 {
+        let recent = self
+            .samples
+            .iter()
+            .filter(|(time, _)| now.duration_since(*time) <= RateMeter::WINDOW);
+        let generations: u64 = recent.map(|(_, generations)| generations).sum();
+        generations as f64 / RateMeter::WINDOW.as_secs_f64()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChartWindow {
+    Last100,
+    Last1000,
+    All,
+}
+
+impl ChartWindow {
+    pub fn next(&self) -> Self {
+        match self {
+            ChartWindow::Last100 => ChartWindow::Last1000,
+            ChartWindow::Last1000 => ChartWindow::All,
+            ChartWindow::All => ChartWindow::Last100,
+        }
+    }
+
+    fn generations(&self) -> Option<u64> {
+        match self {
+            ChartWindow::Last100 => Some(100),
+            ChartWindow::Last1000 => Some(1000),
+            ChartWindow::All => None,
+        }
+    }
+}
+
+impl fmt::Display for ChartWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.generations() {
+            Some(generations) => write!(f, "last {}", generations),
+            None => write!(f, "all"),
+        }
+    }
+}
+
+// Population of each generation reached, as (generation, population)
+#[derive(Default)]
+pub struct PopulationHistory {
+    samples: Vec<(u64, u64)>,
+}
+
+impl PopulationHistory {
+    // replaces what was recorded for generation and any later ones
+    pub fn record(&mut self, generation: u64, population: u64) {
+        let kept = self.samples.partition_point(|(g, _)| *g < generation);
+        self.samples.truncate(kept);
+        self.samples.push((generation, population));
+    }
+
+    pub fn window(&self, window: ChartWindow) -> &[(u64, u64)] {
+        let last = self.samples.last().map_or(0, |(generation, _)| *generation);
+        let first = window
+            .generations()
+            .map_or(0, |generations| last.saturating_sub(generations - 1));
+        let start = self.samples.partition_point(|(g, _)| *g < first);
+        &self.samples[start..]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate() {
+        let start = Instant::now();
+        let mut meter = RateMeter::default();
+        for tick in 0..40 {
+            meter.record(start + Duration::from_millis(tick * 50), 2);
+        }
+        let end = start + Duration::from_millis(39 * 50);
+        assert_eq!(meter.rate(end), 42.0);
+        assert_eq!(meter.rate(end + Duration::from_secs(2)), 0.0);
+    }
+
+    #[test]
+    fn test_population_window() {
+        let mut history = PopulationHistory::default();
+        (0..500).for_each(|generation| history.record(generation, generation * 2));
+        assert_eq!(history.window(ChartWindow::Last100).len(), 100);
+        assert_eq!(history.window(ChartWindow::Last100)[0], (400, 800));
+        assert_eq!(history.window(ChartWindow::Last1000).len(), 500);
+        history.record(250, 7);
+        assert_eq!(history.window(ChartWindow::All).last(), Some(&(250, 7)));
+        assert_eq!(history.window(ChartWindow::All).len(), 251);
+    }
+}
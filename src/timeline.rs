@@ -0,0 +1,192 @@
+use crate::game::Cell;
+use std::collections::VecDeque;
+
+enum Frame {
+    Key(Vec<Vec<Cell>>),
+    // cells that changed since the previous frame, with their new value
+    Delta(Vec<(usize, usize, Cell)>),
+}
+
+impl Frame {
+    // what a frame counts towards Timeline::MAX_CELLS
+    fn size(&self) -> usize {
+        match self {
+            Frame::Key(cells) => cells.iter().map(Vec::len).sum::<usize>().max(1),
+            Frame::Delta(changes) => changes.len().max(1),
+        }
+    }
+}
+
+// Past generations of the board. Now and then the whole board is kept, and the
+// frames in between only hold what changed. A new keyframe is made once the changes
+// since the last one add up to a board's worth of cells, or after KEYFRAME_INTERVAL
+// frames, so going back to any frame replays at most that much. The oldest frames
+// are forgotten once the timeline holds too many frames or cells.
+#[derive(Default)]
+pub struct Timeline {
+    frames: VecDeque<(u64, Frame)>,
+    // cells of the newest frame, which the next one is compared against
+    latest: Vec<Vec<Cell>>,
+    stored: usize,
+}
+
+impl Timeline {
+    pub const MAX_FRAMES: usize = 4096;
+    pub const MAX_CELLS: usize = 1 << 24;
+    const KEYFRAME_INTERVAL: usize = 64;
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn generation(&self, index: usize) -> Option<u64> {
+        self.frames.get(index).map(|(generation, _)| *generation)
+    }
+
+    // newest frame at or before generation
+    pub fn index_of(&self, generation: u64) -> Option<usize> {
+        let after = self.frames.partition_point(|(g, _)| *g <= generation);
+        after.checked_sub(1)
+    }
+
+    pub fn frame(&self, index: usize) -> Option<Vec<Vec<Cell>>> {
+        let (key, cells) = (0..=index).rev().find_map(|i| match self.frames.get(i) {
+            Some((_, Frame::Key(cells))) => Some((i, cells)),
+            _ => None,
+        })?;
+        let mut cells = cells.clone();
+        for (_, frame) in self.frames.range(key + 1..=index) {
+            if let Frame::Delta(changes) = frame {
+                for &(row, column, cell) in changes {
+                    cells[row][column] = cell;
+                }
+            }
+        }
+        Some(cells)
+    }
+
+    // store the board at generation, replacing that generation and any later ones
+    pub fn record(&mut self, generation: u64, cells: &[Vec<Cell>]) {
+        let kept = self.frames.partition_point(|(g, _)| *g < generation);
+        if kept < self.frames.len() {
+            self.stored -= self
+                .frames
+                .range(kept..)
+                .map(|(_, frame)| frame.size())
+                .sum::<usize>();
+            self.frames.truncate(kept);
+            self.latest = kept
+                .checked_sub(1)
+                .and_then(|i| self.frame(i))
+                .unwrap_or_default();
+        }
+
+        let since_key: Vec<usize> = self
+            .frames
+            .iter()
+            .rev()
+            .take_while(|(_, frame)| matches!(frame, Frame::Delta(_)))
+            .map(|(_, frame)| frame.size())
+            .collect();
+        let area = cells.iter().map(Vec::len).sum::<usize>();
+        let same_size = self.latest.len() == cells.len()
+            && self.latest.first().map(Vec::len) == cells.first().map(Vec::len);
+        let keyframe = self.frames.is_empty()
+            || !same_size
+            || since_key.len() + 1 >= Timeline::KEYFRAME_INTERVAL
+            || since_key.iter().sum::<usize>() >= area;
+        let frame = match keyframe {
+            true => Frame::Key(cells.to_vec()),
+            false => Frame::Delta(
+                self.latest
+                    .iter()
+                    .zip(cells)
+                    .enumerate()
+                    .flat_map(|(row, (old, new))| {
+                        old.iter()
+                            .zip(new)
+                            .enumerate()
+                            .filter(|(_, (old, new))| old != new)
+                            .map(move |(column, (_, new))| (row, column, *new))
+                    })
+                    .collect(),
+            ),
+        };
+        self.stored += frame.size();
+        self.frames.push_back((generation, frame));
+        self.latest = cells.to_vec();
+
+        // the oldest remaining frame has to hold the whole board
+        while self.frames.len() > Timeline::MAX_FRAMES || self.stored > Timeline::MAX_CELLS {
+            let Some(second) = self.frame(1) else {
+                break;
+            };
+            let (_, oldest) = self.frames.pop_front().unwrap();
+            let key = Frame::Key(second);
+            self.stored = self.stored - oldest.size() - self.frames[0].1.size() + key.size();
+            self.frames[0].1 = key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn board(generation: usize) -> Vec<Vec<Cell>> {
+        let mut cells = vec![vec![Cell::Dead; 8]; 8];
+        cells[generation % 8][generation / 8 % 8] = Cell::Alive;
+        cells
+    }
+
+    #[test]
+    fn test_replay_frames() {
+        let mut timeline = Timeline::default();
+        let total = Timeline::MAX_FRAMES + 100;
+        for generation in 0..total {
+            timeline.record(generation as u64, &board(generation));
+        }
+        assert_eq!(timeline.len(), Timeline::MAX_FRAMES);
+        assert_eq!(timeline.generation(0), Some(100));
+        for index in [0, 1, 63, 64, 65, 1000, Timeline::MAX_FRAMES - 1] {
+            let generation = timeline.generation(index).unwrap() as usize;
+            assert_eq!(timeline.frame(index), Some(board(generation)));
+        }
+
+        // recording an earlier generation drops everything after it
+        timeline.record(150, &board(0));
+        assert_eq!(timeline.len(), 51);
+        assert_eq!(timeline.index_of(4000), Some(50));
+        assert_eq!(timeline.frame(50), Some(board(0)));
+        timeline.record(151, &board(9));
+        assert_eq!(timeline.frame(51), Some(board(9)));
+    }
+
+    #[test]
+    fn test_stored_cells_bound() {
+        // every generation of a large board changes all of it
+        let side = 512;
+        let board = |generation: usize| {
+            let cell = if generation.is_multiple_of(2) {
+                Cell::Dead
+            } else {
+                Cell::Alive
+            };
+            vec![vec![cell; side]; side]
+        };
+        let mut timeline = Timeline::default();
+        for generation in 0..100 {
+            timeline.record(generation as u64, &board(generation));
+        }
+        assert!(timeline.stored <= Timeline::MAX_CELLS);
+        assert_eq!(timeline.len(), Timeline::MAX_CELLS / (side * side));
+        assert_eq!(timeline.generation(timeline.len() - 1), Some(99));
+        let oldest = timeline.generation(0).unwrap() as usize;
+        assert_eq!(timeline.frame(0), Some(board(oldest)));
+        assert_eq!(timeline.frame(5), Some(board(oldest + 5)));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::game::Position;
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    // cells beyond the edges are always dead
+    Plane,
+    // shift_x moves columns when crossing the top/bottom edges, shift_y moves rows
+    // when crossing the left/right edges
+    Torus { shift_x: i64, shift_y: i64 },
+    // one pair of edges is joined with a twist, reversing the other coordinate
+    KleinBottle { twisted_top_bottom: bool },
+    CrossSurface,
+    // top edge joined to the left edge and bottom edge to the right edge
+    Sphere,
+    // the board is a window onto an unbounded plane stored by the engine, so edits
+    // past the window edges are dropped like on a bounded plane
+    Unbounded,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Topology::Torus {
+            shift_x: 0,
+            shift_y: 0,
+        }
+    }
+}
+
+impl Topology {
+    // map any coordinates, including ones beyond the edges, to the cell they refer to
+    pub fn locate(&self, row: i64, column: i64, width: u16, height: u16) -> Option<Position> {
+        let (width, height) = (width as i64, height as i64);
+        let odd = |crossings: i64| crossings % 2 != 0;
+        let (row, column) = match *self {
+            Topology::Plane | Topology::Unbounded => (row, column),
+            Topology::Torus { shift_x, shift_y } => {
+                let column = column + shift_x * row.div_euclid(height);
+                let row = row + shift_y * column.div_euclid(width);
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+            Topology::KleinBottle {
+                twisted_top_bottom: true,
+            } => {
+                let column = match odd(row.div_euclid(height)) {
+                    true => width - 1 - column,
+                    false => column,
+                };
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+            Topology::KleinBottle {
+                twisted_top_bottom: false,
+            } => {
+                let row = match odd(column.div_euclid(width)) {
+                    true => height - 1 - row,
+                    false => row,
+                };
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+            Topology::CrossSurface => {
+                let column = match odd(row.div_euclid(height)) {
+                    true => width - 1 - column,
+                    false => column,
+                };
+                let row = match odd(column.div_euclid(width)) {
+                    true => height - 1 - row.rem_euclid(height),
+                    false => row.rem_euclid(height),
+                };
+                (row, column.rem_euclid(width))
+            }
+            Topology::Sphere => fold_sphere(row, column, width)?,
+        };
+        match (0..height).contains(&row) && (0..width).contains(&column) {
+            true => Some((row as usize, column as usize).into()),
+            false => None,
+        }
+    }
+}
+
+// Carry a point across the joined edges of a sphere until it lands on the board. The
+// corners where two joined edges meet have no diagonal neighbor beyond them.
+fn fold_sphere(row: i64, column: i64, size: i64) -> Option<(i64, i64)> {
+    let (mut row, mut column) = (row, column);
+    for _ in 0..4 {
+        let outside = |n: i64| !(0..size).contains(&n);
+        (row, column) = match (row, column) {
+            (r, c) if outside(r) && outside(c) => return None,
+            (r, c) if r < 0 => (c, -1 - r),
+            (r, c) if r >= size => (c, 2 * size - 1 - r),
+            (r, c) if c < 0 => (-1 - c, r),
+            (r, c) if c >= size => (2 * size - 1 - c, r),
+            inside => return Some(inside),
+        };
+    }
+    None
+}
+
+// A Golly style grid description such as T64,32 or K64*,32, giving both the
+// topology and the size of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridSpec {
+    pub topology: Topology,
+    pub width: u16,
+    pub height: u16,
+}
+
+struct Edge {
+    size: u16,
+    shift: i64,
+    twisted: bool,
+}
+
+fn parse_edge(part: &str) -> Result<Edge, String> {
+    let (part, twisted) = match part.strip_suffix('*') {
+        Some(part) => (part, true),
+        None => (part, false),
+    };
+    let (size, shift) = match part.find(['+', '-']) {
+        Some(i) => (&part[..i], part[i..].trim_start_matches('+')),
+        None => (part, "0"),
+    };
+    let size = match size.parse::<u16>() {
+        Ok(size) if size > 0 => size,
+        _ => return Err(format!("'{}' is not a grid size", size)),
+    };
+    let shift = shift
+        .parse::<i64>()
+        .map_err(|_| format!("'{}' is not a shift", shift))?;
+    Ok(Edge {
+        size,
+        shift,
+        twisted,
+    })
+}
+
+impl FromStr for GridSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim().to_ascii_uppercase();
+        let invalid = |reason: &str| format!("invalid topology '{}': {}", s, reason);
+        let mut chars = spec.chars();
+        let kind = chars.next().ok_or_else(|| invalid("empty"))?;
+        let edges = chars
+            .as_str()
+            .split(',')
+            .map(parse_edge)
+            .collect::<Result<Vec<Edge>, String>>()
+            .map_err(|e| invalid(&e))?;
+
+        let (topology, width, height) = match (kind, &edges[..]) {
+            ('S', [edge]) if edge.shift == 0 && !edge.twisted => {
+                (Topology::Sphere, edge.size, edge.size)
+            }
+            ('S', _) => return Err(invalid("a sphere takes a single size, e.g. S64")),
+            (_, [x, y]) => {
+                let topology = match kind {
+                    'P' if x.shift == 0 && y.shift == 0 && !x.twisted && !y.twisted => {
+                        Topology::Plane
+                    }
+                    'T' if !x.twisted && !y.twisted && (x.shift == 0 || y.shift == 0) => {
+                        Topology::Torus {
+                            shift_x: x.shift,
+                            shift_y: y.shift,
+                        }
+                    }
+                    'K' if x.twisted != y.twisted && x.shift == 0 && y.shift == 0 => {
+                        Topology::KleinBottle {
+                            twisted_top_bottom: x.twisted,
+                        }
+                    }
+                    'C' if x.shift == 0 && y.shift == 0 && !x.twisted && !y.twisted => {
+                        Topology::CrossSurface
+                    }
+                    'P' | 'T' | 'K' | 'C' => {
+                        return Err(invalid("unsupported shift or twist for this topology"))
+                    }
+                    _ => return Err(invalid("expected one of P, T, K, C or S")),
+                };
+                (topology, x.size, y.size)
+            }
+            _ => return Err(invalid("expected <width>,<height>")),
+        };
+        Ok(GridSpec {
+            topology,
+            width,
+            height,
+        })
+    }
+}
+
+impl fmt::Display for GridSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shift = |shift: i64| match shift {
+            0 => String::new(),
+            _ => format!("{:+}", shift),
+        };
+        let (width, height) = (self.width, self.height);
+        match self.topology {
+            Topology::Plane => write!(f, "P{},{}", width, height),
+            Topology::Torus { shift_x, shift_y } => write!(
+                f,
+                "T{}{},{}{}",
+                width,
+                shift(shift_x),
+                height,
+                shift(shift_y)
+            ),
+            Topology::KleinBottle {
+                twisted_top_bottom: true,
+            } => write!(f, "K{}*,{}", width, height),
+            Topology::KleinBottle {
+                twisted_top_bottom: false,
+            } => write!(f, "K{},{}*", width, height),
+            Topology::CrossSurface => write!(f, "C{},{}", width, height),
+            Topology::Sphere => write!(f, "S{}", width),
+            Topology::Unbounded => write!(f, "unbounded"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn locate(topology: Topology, row: i64, column: i64) -> Option<(usize, usize)> {
+        topology.locate(row, column, 8, 4).map(|pos| pos.into())
+    }
+
+    #[test]
+    fn test_parse_grid_spec() {
+        for spec in [
+            "T64,32", "T64+5,32", "T64,32-3", "P64,32", "K64*,32", "K64,32*", "C10,20", "S16",
+        ] {
+            assert_eq!(spec.parse::<GridSpec>().unwrap().to_string(), spec);
+        }
+        assert_eq!(
+            "k64*,32".parse(),
+            Ok(GridSpec {
+                topology: Topology::KleinBottle {
+                    twisted_top_bottom: true
+                },
+                width: 64,
+                height: 32,
+            })
+        );
+        for invalid in ["T64", "K64,32", "P64+1,32", "T0,5", "S4,4", "X4,4"] {
+            assert!(invalid.parse::<GridSpec>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        let torus = Topology::default();
+        assert_eq!(locate(torus, -1, -1), Some((3, 7)));
+        assert_eq!(locate(torus, 4, 17), Some((0, 1)));
+        assert_eq!(locate(Topology::Plane, -1, 0), None);
+        let shifted = Topology::Torus {
+            shift_x: 2,
+            shift_y: 0,
+        };
+        assert_eq!(locate(shifted, 4, 7), Some((0, 1)));
+        assert_eq!(locate(shifted, -1, 0), Some((3, 6)));
+        let klein = Topology::KleinBottle {
+            twisted_top_bottom: true,
+        };
+        assert_eq!(locate(klein, -1, 1), Some((3, 6)));
+        assert_eq!(locate(klein, 1, 8), Some((1, 0)));
+        assert_eq!(locate(Topology::CrossSurface, 1, -1), Some((2, 7)));
+        let sphere = |row, column| Topology::Sphere.locate(row, column, 4, 4).map(|p| p.into());
+        assert_eq!(sphere(-1, 2), Some((2, 0)));
+        assert_eq!(sphere(1, 4), Some((3, 1)));
+        assert_eq!(sphere(-1, -1), None);
+    }
+}
@@ -1,46 +1,115 @@
+use std::cmp::Ordering;
 use tui::{
     buffer::{self, Buffer},
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{self, Color, Style},
-    widgets::{Block, Borders, List, ListItem, Widget},
+    text::Spans,
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Sparkline, Widget, Wrap},
 };
 
-use crate::game::{Board, Cell, Shape};
+use crate::{
+    census::Census,
+    game::{Board, Cell, GolState, Position, Shape},
+    stats::{ChartWindow, Stats},
+    timeline::Timeline,
+    viewport::{RenderMode, Viewport},
+};
 
 pub struct GolUi<'a> {
-    pub game_row: Rect,
     pub game_area: Rect,
     pub controls_row: Rect,
     pub controls_list_area: Rect,
     pub shape_display_area: Rect,
+    pub chart_area: Rect,
     pub playpause_toggle_area: Rect,
+    pub timeline_area: Rect,
+    pub stats_area: Rect,
     pub screen_border: Block<'a>,
     pub controls_border: Block<'a>,
     pub controls_list: List<'a>,
 }
 
 impl GolUi<'_> {
-    pub fn new(term_size: Rect, game_board: &Board) -> Self {
+    const CONTROLS: [&'static str; 20] = [
+        "SPACE      : Play/Pause",
+        "← / →      : Step back / forward",
+        "Click/Drag : Paint cells, right: erase",
+        "Alt-Click  : Add shape at position",
+        "TAB (was S): Next preset/drop clipboard",
+        "'B'        : Brush: draw/erase/toggle",
+        "'C' / 'R'  : Clear / Randomize",
+        "WASD/Wheel : Move view, Home: center",
+        "'[' ']' 'M': Zoom out/in, Render mode",
+        "'U'/Ctrl-Y : Undo / Redo",
+        "'+' / '-'  : Faster / Slower",
+        "'N'        : Jump ahead N gens",
+        "'G' / 'O'  : Chart window / Census",
+        "'E'        : Cursor (hjkl X:flip I:add)",
+        "Shift-Drag : Select, 'V' from cursor",
+        "Ctrl-C/X/V : Copy/Cut/Paste, 'F' fill",
+        "'T' | _ /  : Turn, flip H/V, transpose",
+        "Ctrl-S     : Save board to file",
+        "'?'        : Show/hide all controls",
+        "ESC or 'Q' : Quit",
+    ];
+    // the few controls always shown, the rest are behind '?'
+    const HELP: [&'static str; 6] = [
+        "SPACE      : Play/Pause",
+        "← / →      : Step back / forward",
+        "Click/Drag : Paint cells, right: erase",
+        "'U'/Ctrl-Y : Undo / Redo",
+        "'?'        : All controls",
+        "ESC or 'Q' : Quit",
+    ];
+    // the controls box holds the play toggle with all the stats below it, plus the
+    // heading gap, the timeline and the margins around them
+    const CONTROLS_HEIGHT: u16 = ControlToggle::HEIGHT + Stats::ROWS + 7;
+    // on short terminals it shrinks down to just the help list
+    const MIN_CONTROLS_HEIGHT: u16 = GolUi::HELP.len() as u16 + 7;
+    const MIN_BOARD_ROWS: u16 = 4;
+    // room for the help list with the shape preview and play toggle beside it,
+    // and a few rows of board above
+    const MIN_WIDTH: u16 = 60;
+    const MIN_HEIGHT: u16 =
+        GolUi::MIN_CONTROLS_HEIGHT + Board::GAME_BOARD_TOP * 2 + GolUi::MIN_BOARD_ROWS;
+
+    pub fn fits(term_size: Rect) -> bool {
+        term_size.width >= GolUi::MIN_WIDTH && term_size.height >= GolUi::MIN_HEIGHT
+    }
+
+    pub fn new(term_size: Rect, game_state: &GolState) -> Self {
+        let game_board = &game_state.game_board;
+        // show as much of the board as fits between the borders and the controls
+        let (view_columns, view_rows) = game_state
+            .viewport
+            .view_size(game_board.width, game_board.height);
+        let controls_height = term_size
+            .height
+            .saturating_sub(Board::GAME_BOARD_TOP * 2 + GolUi::MIN_BOARD_ROWS)
+            .clamp(GolUi::MIN_CONTROLS_HEIGHT, GolUi::CONTROLS_HEIGHT);
+        let free_rows = term_size
+            .height
+            .saturating_sub(controls_height + Board::GAME_BOARD_TOP * 2);
+        let game_height = view_rows.min(free_rows);
+        let char_width = game_state.viewport.mode.char_width();
+        let game_width =
+            view_columns.min(term_size.width.saturating_sub(4) / char_width * char_width);
         let screen_rows = Layout::default()
             .constraints(
                 [
-                    Constraint::Length(game_board.height + 10),
-                    Constraint::Length(10),
+                    Constraint::Length(game_height + Board::GAME_BOARD_TOP * 2),
+                    Constraint::Length(controls_height),
                 ]
                 .as_ref(),
             )
             .split(term_size);
-        let board_margin_width = if term_size.width > game_board.width * 2 {
-            (term_size.width - (game_board.width * 2)) / 2
-        } else {
-            0
-        };
+        let board_margin_width = term_size.width.saturating_sub(game_width) / 2;
 
         let game_row_columns = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
                 Constraint::Length(board_margin_width),
-                Constraint::Length(game_board.width * 2 + 2),
+                Constraint::Length(game_width),
                 Constraint::Length(board_margin_width),
             ])
             .vertical_margin(Board::GAME_BOARD_TOP)
@@ -49,9 +118,9 @@ impl GolUi<'_> {
         let controls_row_columns = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Length((term_size.width - 38) / 2),
+                Constraint::Length(term_size.width.saturating_sub(38) / 2),
                 Constraint::Min(38),
-                Constraint::Length((term_size.width - 38) / 2),
+                Constraint::Length(term_size.width.saturating_sub(38) / 2),
             ])
             .vertical_margin(2)
             .split(screen_rows[1]);
@@ -59,29 +128,40 @@ impl GolUi<'_> {
         let controls_main_column_rows = Layout::default()
             .constraints([
                 Constraint::Length(2),
-                Constraint::Length(7),
-                Constraint::Min(1),
+                Constraint::Min(GolUi::HELP.len() as u16),
+                Constraint::Length(1),
             ])
             .split(controls_row_columns[1]);
 
+        // the side columns stop short of the timeline
         let controls_left_column_rows = Layout::default()
             .constraints([
                 Constraint::Length(2),
                 Constraint::Length(7),
-                Constraint::Min(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
             ])
             .split(controls_row_columns[0]);
 
         let controls_right_column_rows = Layout::default()
             .constraints([
                 Constraint::Length(2),
-                Constraint::Length(7),
-                Constraint::Min(1),
+                Constraint::Length(ControlToggle::HEIGHT),
+                Constraint::Min(0),
+                Constraint::Length(1),
             ])
             .split(controls_row_columns[2]);
 
         let screen_border = Block::default()
-            .title("Game of Life")
+            .title(match &game_state.message {
+                Some(message) => format!(
+                    "Game of Life [{} {}] - {}",
+                    game_board.rule,
+                    game_board.grid(),
+                    message
+                ),
+                None => format!("Game of Life [{} {}]", game_board.rule, game_board.grid()),
+            })
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Green));
 
@@ -90,23 +170,28 @@ impl GolUi<'_> {
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Green));
 
-        let controls_list = List::new(vec![
-            ListItem::new("SPACE      : Play/Pause"),
-            ListItem::new("Right →    : Next gen (if PAUSED)"),
-            ListItem::new("Click      : Toggle cell at position"),
-            ListItem::new("Alt-Click  : Add shape at position"),
-            ListItem::new("TAB        : Change shape selection"),
-            ListItem::new("'C' / 'R'  : Clear / Randomize"),
-            ListItem::new("ESC or 'Q' : Quit"),
-        ]);
+        let controls_list = List::new(GolUi::HELP.map(ListItem::new).to_vec());
 
         GolUi {
-            game_row: screen_rows[0],
             game_area: game_row_columns[1],
             controls_row: screen_rows[1],
             controls_list_area: controls_main_column_rows[1],
             shape_display_area: controls_left_column_rows[1],
+            // clear of the controls border and the list beside it
+            chart_area: Rect {
+                x: controls_left_column_rows[2].x + 2,
+                width: controls_left_column_rows[2].width.saturating_sub(4),
+                ..controls_left_column_rows[2]
+            },
             playpause_toggle_area: controls_right_column_rows[1],
+            stats_area: controls_right_column_rows[2],
+            // below the controls list, spanning the controls box
+            timeline_area: Rect {
+                x: screen_rows[1].x + 2,
+                width: screen_rows[1].width.saturating_sub(4),
+                height: 1,
+                ..controls_main_column_rows[2]
+            },
             screen_border,
             controls_border,
             controls_list,
@@ -114,12 +199,272 @@ impl GolUi<'_> {
     }
 }
 
+impl Stats {
+    const ROWS: u16 = 11;
+}
+
+impl Widget for Stats {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let size = match self.bounds {
+            Some((left, top, right, bottom)) => {
+                format!("{}x{}", right - left + 1, bottom - top + 1)
+            }
+            None => String::from("-"),
+        };
+        let (stable, period) = match self.cycle {
+            Some(cycle) => (
+                format!("Stabilized at gen {}", cycle.generation),
+                match cycle.displacement {
+                    (0, 0) => format!("Period {}", cycle.period),
+                    (x, y) => format!(
+                        "Period {}, speed ({},{})/{}",
+                        cycle.period, x, y, cycle.period
+                    ),
+                },
+            ),
+            None => (String::new(), String::new()),
+        };
+        let selected = [("Sel", self.selection), ("Clip", self.clipboard)]
+            .iter()
+            .filter_map(|(label, size)| size.map(|(w, h)| format!("{} {}x{}", label, w, h)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let lines: [String; Stats::ROWS as usize] = [
+            format!("Gen   {}", self.generation),
+            format!("Pop   {}", self.population),
+            match self.step {
+                0 | 1 => format!("+/-   {}/{}", self.births, self.deaths),
+                // counted across a whole step of several generations
+                step => format!("+/-   {}/{} ({}g)", self.births, self.deaths, step),
+            },
+            format!("Box   {}", size),
+            format!("Speed {}/s", self.speed),
+            format!("Rate  {:.1}/s", self.rate),
+            format!("Brush {}", self.brush),
+            match self.cursor {
+                Some((x, y)) => format!("Cursor {},{}", x, y),
+                None => String::new(),
+            },
+            selected,
+            stable,
+            period,
+        ];
+        let style = Style::default().fg(Color::Green);
+        for (line, y) in lines.iter().zip(area.top()..area.bottom()) {
+            buf.set_stringn(area.x, y, line, area.width as usize, style);
+        }
+    }
+}
+
+// Population over the last few generations, squeezed into the width of the area by
+// taking the largest population in each column
+pub struct PopulationChart<'a> {
+    pub samples: &'a [(u64, u64)],
+    pub window: ChartWindow,
+}
+
+impl Widget for PopulationChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!("Pop ({})", self.window))
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.width == 0 {
+            return;
+        }
+        let per_column = self.samples.len().div_ceil(inner.width as usize);
+        let data: Vec<u64> = self
+            .samples
+            .chunks(per_column.max(1))
+            .map(|chunk| chunk.iter().map(|(_, population)| *population).max())
+            .map(Option::unwrap_or_default)
+            .collect();
+        Sparkline::default()
+            .data(&data)
+            .style(Style::default().fg(Color::Green))
+            .render(inner, buf);
+    }
+}
+
+// Objects making up the pattern, most common first
+pub struct CensusPanel<'a> {
+    pub census: Option<&'a Census>,
+    // whether the pattern has stopped changing, so the census is final
+    pub settled: bool,
+}
+
+impl Widget for CensusPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(match self.settled {
+                true => "Census (settled)",
+                false => "Census",
+            })
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let mut lines: Vec<String> = match self.census {
+            Some(census) if census.objects.is_empty() => vec![String::from("Nothing left")],
+            Some(census) => census
+                .objects
+                .iter()
+                .map(|(name, count)| format!("{:>4} {}", count, name))
+                .collect(),
+            None => vec![String::from("Too large to count")],
+        };
+        let rows = inner.height as usize;
+        if lines.len() > rows && rows > 0 {
+            let hidden = lines.len() - rows + 1;
+            lines.truncate(rows - 1);
+            lines.push(format!("     and {} more", hidden));
+        }
+        let style = Style::default().fg(Color::Green);
+        for (line, y) in lines.iter().zip(inner.top()..inner.bottom()) {
+            buf.set_stringn(inner.x, y, line, inner.width as usize, style);
+        }
+    }
+}
+
+// Stored generations as a bar that can be clicked or dragged along, with the
+// current generation marked
+pub struct TimelineBar<'a> {
+    pub timeline: &'a Timeline,
+    pub generation: u64,
+}
+
+impl TimelineBar<'_> {
+    const LABEL_WIDTH: u16 = 12;
+
+    fn bar(area: Rect) -> Rect {
+        let width = area.width.saturating_sub(TimelineBar::LABEL_WIDTH * 2);
+        Rect::new(
+            area.x + TimelineBar::LABEL_WIDTH,
+            area.y,
+            width,
+            area.height.min(1),
+        )
+    }
+
+    // stored frame under a screen position in the area showing the timeline
+    pub fn frame_at(timeline: &Timeline, area: Rect, row: u16, column: u16) -> Option<usize> {
+        let bar = TimelineBar::bar(area);
+        let inside = row == bar.y && (bar.left()..bar.right()).contains(&column);
+        match inside && !timeline.is_empty() {
+            true => {
+                let last = timeline.len() - 1;
+                let offset = (column - bar.x) as usize;
+                Some(offset * last / (bar.width as usize - 1).max(1))
+            }
+            false => None,
+        }
+    }
+}
+
+impl Widget for TimelineBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let bar = TimelineBar::bar(area);
+        if bar.width < 2 || self.timeline.is_empty() {
+            return;
+        }
+        let last = self.timeline.len() - 1;
+        let current = self.timeline.index_of(self.generation).unwrap_or(0);
+        let marker = bar.x + (current * (bar.width as usize - 1) / last.max(1)) as u16;
+        let style = Style::default().fg(Color::Green);
+        buf.set_stringn(
+            area.x,
+            area.y,
+            format!("gen {}", self.generation),
+            TimelineBar::LABEL_WIDTH as usize - 1,
+            style,
+        );
+        for x in bar.left()..bar.right() {
+            let symbol = match x.cmp(&marker) {
+                Ordering::Less => "━",
+                Ordering::Equal => "●",
+                Ordering::Greater => "─",
+            };
+            buf.get_mut(x, bar.y).set_symbol(symbol).set_style(style);
+        }
+        let newest = self.timeline.generation(last).unwrap_or_default();
+        buf.set_stringn(
+            bar.right() + 1,
+            area.y,
+            newest.to_string(),
+            TimelineBar::LABEL_WIDTH as usize - 1,
+            style,
+        );
+    }
+}
+
+// Every control, drawn over the middle of the screen while toggled on with '?'
+pub struct HelpOverlay;
+
+impl Widget for HelpOverlay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let longest = GolUi::CONTROLS
+            .iter()
+            .map(|line| line.chars().count())
+            .max();
+        let width = (longest.unwrap_or(0) as u16 + 4).min(area.width);
+        let height = (GolUi::CONTROLS.len() as u16 + 2).min(area.height);
+        let overlay = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let block = Block::default()
+            .title("All controls")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        let inner = block.inner(overlay);
+        Clear.render(overlay, buf);
+        block.render(overlay, buf);
+        List::new(GolUi::CONTROLS.map(ListItem::new).to_vec()).render(
+            Rect {
+                x: inner.x + 1,
+                width: inner.width.saturating_sub(1),
+                ..inner
+            },
+            buf,
+        );
+    }
+}
+
+// Shown instead of the game while the terminal can't hold the layout
+pub struct TooSmall;
+
+impl Widget for TooSmall {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let message = format!(
+            "Terminal too small: {}x{}, need at least {}x{}",
+            area.width,
+            area.height,
+            GolUi::MIN_WIDTH,
+            GolUi::MIN_HEIGHT
+        );
+        let top = area.height / 2;
+        Paragraph::new(vec![
+            Spans::from(message),
+            Spans::from("ESC or 'Q' to quit"),
+        ])
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .render(
+            Rect::new(area.x, area.y + top, area.width, area.height - top),
+            buf,
+        );
+    }
+}
+
 pub enum ControlToggle {
     Play,
     Pause,
 }
 
 impl ControlToggle {
+    const HEIGHT: u16 = 7;
     const PLAYPAUSE: [(usize, usize); 36] = [
         (0, 0),
         (1, 0),
@@ -178,7 +523,7 @@ impl Widget for ControlToggle {
         });
         let draw_width = if area.width < 20 { area.width } else { 20 };
         let margin = if width > draw_width {
-            (width - draw_width) / 2 - 2
+            ((width - draw_width) / 2).saturating_sub(2)
         } else {
             0
         };
@@ -202,49 +547,179 @@ impl Widget for ControlToggle {
     }
 }
 
-impl Widget for Board {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let content_cells: Vec<Vec<buffer::Cell>> = self
-            .cells
-            .into_iter()
-            .map(|row| {
-                row.into_iter()
-                    .map(|cell| buffer::Cell {
-                        symbol: String::from("\u{25A0}"),
-                        fg: match cell {
-                            Cell::Alive => Color::Black,
-                            Cell::Dead => Color::White,
-                        },
-                        ..Default::default()
-                    })
-                    .collect()
+// each decay state of a Generations rule fades from red towards the dead color
+fn dying_color(age: u8, states: u8) -> Color {
+    let fade = age as f32 / (states - 1) as f32;
+    let channel = |from: f32, to: f32| (from + (to - from) * fade) as u8;
+    Color::Rgb(
+        channel(176.0, 240.0),
+        channel(16.0, 224.0),
+        channel(32.0, 208.0),
+    )
+}
+
+// quadrant glyphs indexed by their filled quarters: 1 top left, 2 top right,
+// 4 bottom left and 8 bottom right
+const QUADRANTS: [&str; 16] = [
+    " ", "▘", "▝", "▀", "▖", "▌", "▞", "▛", "▗", "▚", "▐", "▜", "▄", "▙", "▟", "█",
+];
+// Braille dot bits for each (column, row) of a 2x4 character
+const BRAILLE_DOTS: [(usize, usize, u32); 8] = [
+    (0, 0, 0x01),
+    (0, 1, 0x02),
+    (0, 2, 0x04),
+    (1, 0, 0x08),
+    (1, 1, 0x10),
+    (1, 2, 0x20),
+    (0, 3, 0x40),
+    (1, 3, 0x80),
+];
+
+// A board drawn through a viewport in its render mode
+pub struct BoardView<'a> {
+    pub board: &'a Board,
+    pub viewport: Viewport,
+    pub cursor: Option<Position>,
+    // (left, top, right, bottom) of the selected cells
+    pub selection: Option<(usize, usize, usize, usize)>,
+    // cells the shape in hand would cover if stamped under the mouse
+    pub ghost: Vec<Position>,
+}
+
+impl BoardView<'_> {
+    // a zoomed out character shows the liveliest of the cells it covers
+    fn cell(&self, row: usize, column: usize) -> Cell {
+        let zoom = self.viewport.zoom;
+        let rows = self.board.cells.iter().skip(row).take(zoom);
+        rows.flat_map(|cells| cells.iter().skip(column).take(zoom))
+            .fold(Cell::Dead, |shown, &cell| match (shown, cell) {
+                (Cell::Alive, _) | (_, Cell::Alive) => Cell::Alive,
+                (Cell::Dying(a), Cell::Dying(b)) => Cell::Dying(a.min(b)),
+                (Cell::Dead, other) | (other, Cell::Dead) => other,
             })
-            .collect();
+    }
+}
 
-        let draw_width = if area.width < self.width * 2 {
-            area.width
-        } else {
-            self.width * 2
-        };
-        let draw_height = if area.height < self.height {
-            area.height
-        } else {
-            self.height
+impl Widget for BoardView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let states = self.board.rule.states;
+        let color = |cell: Cell| match cell {
+            Cell::Alive => Color::Black,
+            Cell::Dying(age) => dying_color(age, states),
+            Cell::Dead => Color::White,
         };
+        let Viewport {
+            top,
+            left,
+            zoom,
+            mode,
+        } = self.viewport;
+        let (char_columns, char_rows) = self.viewport.char_cells();
+        let (view_columns, view_rows) =
+            self.viewport.view_size(self.board.width, self.board.height);
+        let draw_width = area.width.min(view_columns);
+        let draw_height = area.height.min(view_rows);
         for x in 0..draw_width {
             for y in 0..draw_height {
-                if x % 2 == 0 {
-                    buf.get_mut(area.left() + x, area.top() + y)
-                        .clone_from(&content_cells[y as usize][(x / 2) as usize]);
-                } else {
-                    buf.get_mut(area.left() + x, area.top() + y)
-                        .set_symbol(tui::symbols::line::VERTICAL)
-                        .set_fg(Color::Black)
-                        .set_style(Style {
-                            add_modifier: style::Modifier::DIM,
-                            ..Default::default()
-                        });
+                let row = top + y as usize * char_rows;
+                let column = left + (x / mode.char_width()) as usize * char_columns;
+                if row >= self.board.height as usize || column >= self.board.width as usize {
+                    continue;
                 }
+                // cell drawn at (column, row) within this character
+                let part = |c: usize, r: usize| self.cell(row + r * zoom, column + c * zoom);
+                // glyph modes only have room to show living cells
+                let alive = |c: usize, r: usize| part(c, r) == Cell::Alive;
+                let target = buf.get_mut(area.left() + x, area.top() + y);
+                match mode {
+                    RenderMode::Cells if x % 2 == 0 => target.clone_from(&buffer::Cell {
+                        symbol: String::from("\u{25A0}"),
+                        fg: color(part(0, 0)),
+                        ..Default::default()
+                    }),
+                    RenderMode::Cells => {
+                        target
+                            .set_symbol(tui::symbols::line::VERTICAL)
+                            .set_fg(Color::Black)
+                            .set_style(Style {
+                                add_modifier: style::Modifier::DIM,
+                                ..Default::default()
+                            });
+                    }
+                    RenderMode::HalfBlock => {
+                        target
+                            .set_symbol("▀")
+                            .set_fg(color(part(0, 0)))
+                            .set_bg(color(part(0, 1)));
+                    }
+                    RenderMode::Quadrant => {
+                        let filled = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(_, (c, r))| alive(*c, *r))
+                            .fold(0, |bits, (i, _)| bits | 1 << i);
+                        target
+                            .set_symbol(QUADRANTS[filled])
+                            .set_fg(Color::Black)
+                            .set_bg(Color::White);
+                    }
+                    RenderMode::Braille => {
+                        let dots = BRAILLE_DOTS
+                            .into_iter()
+                            .filter(|(c, r, _)| alive(*c, *r))
+                            .fold(0, |bits, (_, _, dot)| bits | dot);
+                        let glyph = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                        target
+                            .set_symbol(&glyph.to_string())
+                            .set_fg(Color::Black)
+                            .set_bg(Color::White);
+                    }
+                }
+                // dead cells of the selection are tinted
+                let selected = self.selection.is_some_and(|(l, t, r, b)| {
+                    row <= b && row + char_rows > t && column <= r && column + char_columns > l
+                });
+                if selected {
+                    if target.fg == Color::White {
+                        target.set_fg(Color::LightBlue);
+                    }
+                    if target.bg == Color::White {
+                        target.set_bg(Color::LightBlue);
+                    }
+                }
+            }
+        }
+
+        // the shape in hand shows through in green, darker over living cells
+        let tint = |color: Color| match color {
+            Color::Black => Color::Green,
+            Color::White | Color::LightBlue => Color::LightGreen,
+            other => other,
+        };
+        for pos in self
+            .ghost
+            .iter()
+            .filter(|pos| pos.row >= top && pos.column >= left)
+        {
+            let x = ((pos.column - left) / char_columns) as u16 * mode.char_width();
+            let y = ((pos.row - top) / char_rows) as u16;
+            if x < draw_width && y < draw_height {
+                let target = buf.get_mut(area.left() + x, area.top() + y);
+                let (fg, bg) = (tint(target.fg), tint(target.bg));
+                target.set_fg(fg).set_bg(bg);
+            }
+        }
+
+        // highlight the character holding the cursor
+        let cursor = self
+            .cursor
+            .filter(|pos| pos.row >= top && pos.column >= left);
+        if let Some(cursor) = cursor {
+            let x = ((cursor.column - left) / char_columns) as u16 * mode.char_width();
+            let y = ((cursor.row - top) / char_rows) as u16;
+            if x < draw_width && y < draw_height {
+                buf.get_mut(area.left() + x, area.top() + y)
+                    .set_bg(Color::Yellow);
             }
         }
     }
@@ -254,17 +729,20 @@ impl Widget for Shape {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let width = if area.width > 9 { area.width } else { 9 };
         let height = if area.height > 6 { area.height } else { 6 };
-        let mut cells = vec![vec![Cell::Dead; width as usize]; height as usize];
+        // two rows above the shape, fewer once it is turned too tall for them, and
+        // anything too large to show is cut off
+        let (rows, columns) = self.size();
+        let top = (area.height as usize).saturating_sub(rows).min(2);
+        let mut cells = vec![
+            vec![Cell::Dead; (width as usize).max(columns + 1)];
+            (height as usize).max(top + rows)
+        ];
 
-        let mut max_column = 0;
         for pos in self.pattern {
-            cells[pos.row + 2][pos.column + 1] = Cell::Alive;
-            if pos.column > max_column {
-                max_column = pos.column;
-            }
+            cells[pos.row + top][pos.column + 1] = Cell::Alive;
         }
 
-        let shape_width = max_column as u16;
+        let shape_width = columns.saturating_sub(1) as u16;
         let draw_width = if area.width > (shape_width + 3) * 2 {
             (shape_width + 3) * 2
         } else {
@@ -299,3 +777,79 @@ impl Widget for Shape {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{Engine, EngineKind, Selection, Transform},
+        rule::Rule,
+        topology::Topology,
+    };
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_layout_at_any_size() {
+        let board = Board::new(64, 32, None, 0.0, Rule::default(), Topology::default());
+        let engine = Engine::new(EngineKind::Dense, &board).unwrap();
+        let mut game = GolState::new(board, engine, Rect::default(), PathBuf::new());
+        game.selection = Some(Selection {
+            anchor: (1, 1).into(),
+            corner: (5, 9).into(),
+        });
+        // the acorn turned upright is taller than the preview
+        game.transform_shape(Transform::Rotate);
+        game.hover = Some((30, 62).into());
+        // a standard terminal holds the whole layout
+        assert!(GolUi::fits(Rect::new(0, 0, 80, 24)));
+        for (width, height) in [
+            (0, 0),
+            (1, 1),
+            (20, 10),
+            (59, 40),
+            (61, 22),
+            (80, 24),
+            (200, 80),
+        ] {
+            let term_size = Rect::new(0, 0, width, height);
+            game.resize(term_size);
+            let mut buf = Buffer::empty(term_size);
+            if !GolUi::fits(term_size) {
+                TooSmall.render(term_size, &mut buf);
+                continue;
+            }
+            let layout = GolUi::new(term_size, &game);
+            let board = BoardView {
+                board: &game.game_board,
+                viewport: game.viewport,
+                cursor: game.cursor.clone(),
+                selection: game.selection.as_ref().map(Selection::bounds),
+                ghost: game.ghost(),
+            };
+            board.render(layout.game_area, &mut buf);
+            layout
+                .controls_list
+                .render(layout.controls_list_area, &mut buf);
+            game.current_shape()
+                .render(layout.shape_display_area, &mut buf);
+            ControlToggle::Pause.render(layout.playpause_toggle_area, &mut buf);
+            let timeline = TimelineBar {
+                timeline: &game.timeline,
+                generation: game.generation,
+            };
+            timeline.render(layout.timeline_area, &mut buf);
+            let chart = PopulationChart {
+                samples: game.populations.window(game.chart_window),
+                window: game.chart_window,
+            };
+            chart.render(layout.chart_area, &mut buf);
+            let census = CensusPanel {
+                census: game.census(),
+                settled: game.stats().cycle.is_some(),
+            };
+            census.render(layout.chart_area, &mut buf);
+            game.stats().render(layout.stats_area, &mut buf);
+            HelpOverlay.render(term_size, &mut buf);
+        }
+    }
+}
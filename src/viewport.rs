@@ -0,0 +1,193 @@
+use crate::game::Position;
+use std::fmt;
+use tui::layout::Rect;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenderMode {
+    // one square per cell, followed by a separator column
+    Cells,
+    // ▀ with the top cell as foreground and the bottom cell as background
+    HalfBlock,
+    // 2x2 cells per character from the quadrant block glyphs
+    Quadrant,
+    // 2x4 cells per character as Braille dots
+    Braille,
+}
+
+impl RenderMode {
+    // board cells (columns, rows) drawn by one character
+    pub fn cell_size(&self) -> (usize, usize) {
+        match self {
+            RenderMode::Cells => (1, 1),
+            RenderMode::HalfBlock => (1, 2),
+            RenderMode::Quadrant => (2, 2),
+            RenderMode::Braille => (2, 4),
+        }
+    }
+
+    // screen columns taken by one character
+    pub fn char_width(&self) -> u16 {
+        match self {
+            RenderMode::Cells => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            RenderMode::Cells => RenderMode::HalfBlock,
+            RenderMode::HalfBlock => RenderMode::Quadrant,
+            RenderMode::Quadrant => RenderMode::Braille,
+            RenderMode::Braille => RenderMode::Cells,
+        }
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderMode::Cells => write!(f, "cells"),
+            RenderMode::HalfBlock => write!(f, "half-block"),
+            RenderMode::Quadrant => write!(f, "quadrant"),
+            RenderMode::Braille => write!(f, "braille"),
+        }
+    }
+}
+
+// The part of the board shown on screen, starting at board row top and column left.
+// Each character draws the cells given by the render mode, and each of those covers
+// a square of zoom x zoom board cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub top: usize,
+    pub left: usize,
+    pub zoom: usize,
+    pub mode: RenderMode,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            top: 0,
+            left: 0,
+            zoom: 1,
+            mode: RenderMode::Cells,
+        }
+    }
+}
+
+impl Viewport {
+    pub const MAX_ZOOM: usize = 16;
+
+    // board cells (columns, rows) under one character
+    pub fn char_cells(&self) -> (usize, usize) {
+        let (columns, rows) = self.mode.cell_size();
+        (columns * self.zoom, rows * self.zoom)
+    }
+
+    // screen (columns, rows) needed to show the whole board
+    pub fn view_size(&self, width: u16, height: u16) -> (u16, u16) {
+        let (columns, rows) = self.char_cells();
+        (
+            width.div_ceil(columns as u16) * self.mode.char_width(),
+            height.div_ceil(rows as u16),
+        )
+    }
+
+    // board cells (columns, rows) shown in a screen area
+    pub fn visible_cells(&self, area: Rect) -> (usize, usize) {
+        let (columns, rows) = self.char_cells();
+        let chars = (area.width / self.mode.char_width()) as usize;
+        (chars * columns, area.height as usize * rows)
+    }
+
+    // move by whole characters, so each step covers more cells when zoomed out
+    pub fn scroll(&mut self, rows: i64, columns: i64) {
+        let (char_columns, char_rows) = self.char_cells();
+        self.top = (self.top as i64 + rows * char_rows as i64).max(0) as usize;
+        self.left = (self.left as i64 + columns * char_columns as i64).max(0) as usize;
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom * 2).min(Viewport::MAX_ZOOM);
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom / 2).max(1);
+    }
+
+    pub fn cycle_mode(&mut self) {
+        self.mode = self.mode.next();
+    }
+
+    // keep the view over a width x height board shown in area
+    pub fn fit(&mut self, width: u16, height: u16, area: Rect) {
+        let (columns, rows) = self.visible_cells(area);
+        self.top = self.top.min((height as usize).saturating_sub(rows));
+        self.left = self.left.min((width as usize).saturating_sub(columns));
+    }
+
+    // scroll just far enough for the board cell at pos to be shown in area
+    pub fn reveal(&mut self, pos: &Position, area: Rect) {
+        let (columns, rows) = self.visible_cells(area);
+        self.top = self
+            .top
+            .min(pos.row)
+            .max((pos.row + 1).saturating_sub(rows));
+        self.left = self
+            .left
+            .min(pos.column)
+            .max((pos.column + 1).saturating_sub(columns));
+    }
+
+    // first board cell under the character at (row, column) of the screen
+    pub fn cell_at(&self, area: Rect, row: u16, column: u16) -> Option<Position> {
+        let inside = (area.top()..area.bottom()).contains(&row)
+            && (area.left()..area.right()).contains(&column);
+        if !inside {
+            return None;
+        }
+        let (char_columns, char_rows) = self.char_cells();
+        let char_column = ((column - area.left()) / self.mode.char_width()) as usize;
+        Some(Position {
+            row: self.top + (row - area.top()) as usize * char_rows,
+            column: self.left + char_column * char_columns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scroll_and_hit_test() {
+        let mut viewport = Viewport::default();
+        viewport.zoom_out();
+        viewport.scroll(3, 5);
+        let area = Rect::new(10, 5, 80, 8);
+        viewport.fit(100, 20, area);
+        assert_eq!((viewport.top, viewport.left), (4, 10));
+        assert_eq!(viewport.cell_at(area, 6, 13), Some((6, 12).into()));
+        assert_eq!(viewport.cell_at(area, 4, 13), None);
+        viewport.reveal(&(20, 2).into(), area);
+        assert_eq!((viewport.top, viewport.left), (5, 2));
+        viewport.zoom_out();
+        viewport.fit(100, 20, area);
+        assert_eq!((viewport.top, viewport.left), (0, 0));
+    }
+
+    #[test]
+    fn test_render_modes() {
+        let mut viewport = Viewport::default();
+        let area = Rect::new(0, 0, 10, 10);
+        viewport.cycle_mode();
+        assert_eq!(viewport.view_size(7, 7), (7, 4));
+        assert_eq!(viewport.cell_at(area, 3, 5), Some((6, 5).into()));
+        viewport.cycle_mode();
+        viewport.cycle_mode();
+        assert_eq!(viewport.mode, RenderMode::Braille);
+        assert_eq!(viewport.view_size(7, 7), (4, 2));
+        assert_eq!(viewport.cell_at(area, 1, 3), Some((4, 6).into()));
+    }
+}